@@ -29,7 +29,7 @@ word.addEventListener("input", async function() {
         resultError.innerText = `${s}: not a word`
         resultError.classList.remove("result-hidden")
       } else {
-        resultError.innerText = `HTTP ${resp.status} ${res}`
+        resultError.innerText = `HTTP ${resp.status} ${JSON.parse(res).message}`
         resultError.classList.remove("result-hidden")
       }
     }
@@ -5,7 +5,8 @@ const solution = document.querySelector("#solution")
 solveBtn.addEventListener("click", async function() {
   const resp = await fetch(`/boggle/solver/solution?board=${encodeURIComponent(board.value)}`)
   if (!resp.ok) {
-    solution.innerText = await resp.text()
+    const err = await resp.json()
+    solution.innerText = err.message
     return
   }
   const respData = await resp.json()
@@ -0,0 +1,107 @@
+const summary = document.querySelector("#summary")
+const richness = document.querySelector("#richness")
+const histogram = document.querySelector("#histogram")
+const players = document.querySelector("#players")
+const missed = document.querySelector("#missed")
+const letters = document.querySelector("#letters")
+
+const svgNS = "http://www.w3.org/2000/svg"
+
+function makeTable(headers, rows) {
+  const table = document.createElement("table")
+  const tr = document.createElement("tr")
+  for (h of headers) {
+    const th = document.createElement("th")
+    th.innerText = h
+    tr.append(th)
+  }
+  table.append(tr)
+  for (row of rows) {
+    const tr = document.createElement("tr")
+    for (cell of row) {
+      const td = document.createElement("td")
+      td.innerText = cell
+      tr.append(td)
+    }
+    table.append(tr)
+  }
+  return table
+}
+
+function drawHistogram(buckets) {
+  const most = Math.max(1, ...buckets.map(b => b.count))
+  const rows = buckets.map(b => {
+    const row = document.createElement("div")
+    row.className = "chart-row"
+    const label = document.createElement("span")
+    label.className = "chart-label"
+    label.innerText = `${b.from}-${b.to}`
+    const bar = document.createElement("span")
+    bar.className = "chart-bar"
+    bar.style.width = `${20 * b.count / most}em`
+    const count = document.createElement("span")
+    count.innerText = b.count
+    row.append(label, bar, count)
+    return row
+  })
+  histogram.replaceChildren(...rows)
+}
+
+// Draw each player's share of the points over time.
+function drawTrend(games) {
+  const width = 300
+  const height = 60
+  const svg = document.createElementNS(svgNS, "svg")
+  svg.setAttribute("width", width)
+  svg.setAttribute("height", height)
+  svg.setAttribute("class", "trend")
+  const most = Math.max(1, ...games.map(g => g.percent))
+  const step = games.length > 1 ? width / (games.length - 1) : 0
+  const points = games.map((g, i) => `${i * step},${height - height * g.percent / most}`)
+  const line = document.createElementNS(svgNS, "polyline")
+  line.setAttribute("points", points.join(" "))
+  svg.append(line)
+  return svg
+}
+
+async function load() {
+  const resp = await fetch("/boggle/stats/data")
+  if (!resp.ok) {
+    const err = await resp.json()
+    summary.innerText = err.message
+    return
+  }
+  const stats = await resp.json()
+
+  summary.innerText = `${stats.games} games`
+  richness.replaceChildren(makeTable(
+    ["", "min", "25%", "median", "75%", "90%", "max"],
+    [["points", "score"], ["words", "words"]].map(([label, key]) => {
+      const p = stats[key]
+      return [label, p.min, p.p25, p.median, p.p75, p.p90, p.max]
+    }),
+  ))
+
+  drawHistogram(stats.score_histogram)
+
+  const playerRows = stats.players.map(p => {
+    const row = document.createElement("div")
+    const title = document.createElement("h4")
+    title.innerText = `${p.name}: ${p.games.length} games, ${p.average_score.toFixed(1)} points, ${p.average_percent.toFixed(1)}% of the board`
+    row.append(title, drawTrend(p.games))
+    return row
+  })
+  players.replaceChildren(...playerRows)
+
+  missed.replaceChildren(makeTable(
+    ["Points", "Word", "Missed"],
+    stats.missed.map(m => [m.score, m.word, `${m.missed} of ${m.boards}`]),
+  ))
+
+  letters.replaceChildren(makeTable(
+    ["Letter", "Seen", "Expected", "Std devs"],
+    stats.letters.slice(0, 5).map(l => [l.letter, l.observed, l.expected.toFixed(1), l.z.toFixed(1)]),
+  ))
+}
+
+load()
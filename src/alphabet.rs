@@ -0,0 +1,282 @@
+use std::sync::{Arc, OnceLock};
+
+use super::dictionary::Letter;
+
+// The letters of a language. A Letter is the position of a tile in its alphabet, so English
+// letters are 'a' = 0 through 'z' = 25. A tile can be more than one character, like the Dutch IJ,
+// and some characters are spelled with other tiles, like the accented letters in French.
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Alphabet {
+    name: String,
+    tiles: Vec<Tile>,
+    // Characters that words can use but that don't have their own tile.
+    folds: Vec<(char, &'static str)>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Tile {
+    /// The tile's letters in a word, in lower case.
+    text: String,
+    /// How the tile is written in a board file.
+    board: char,
+}
+
+const ENGLISH: &str = "abcdefghijklmnopqrstuvwxyz";
+
+/// The names that `named` knows.
+pub const NAMES: &[&str] = &["english", "spanish", "french", "german", "dutch"];
+
+impl Alphabet {
+    pub fn english() -> Arc<Self> {
+        static ENGLISH_ALPHABET: OnceLock<Arc<Alphabet>> = OnceLock::new();
+        ENGLISH_ALPHABET
+            .get_or_init(|| Arc::new(Self::new("english", ENGLISH.chars(), &[])))
+            .clone()
+    }
+
+    pub fn named(name: &str) -> Option<Self> {
+        const ACCENTS: &[(char, &str)] = &[
+            ('á', "a"),
+            ('à', "a"),
+            ('â', "a"),
+            ('ä', "a"),
+            ('ç', "c"),
+            ('é', "e"),
+            ('è', "e"),
+            ('ê', "e"),
+            ('ë', "e"),
+            ('í', "i"),
+            ('î', "i"),
+            ('ï', "i"),
+            ('ó', "o"),
+            ('ô', "o"),
+            ('ö', "o"),
+            ('ú', "u"),
+            ('ù', "u"),
+            ('û', "u"),
+            ('ü', "u"),
+            ('ÿ', "y"),
+            ('æ', "ae"),
+            ('œ', "oe"),
+        ];
+        let res = match name {
+            "english" => Self::new(name, ENGLISH.chars(), &[]),
+            // ñ is its own letter in Spanish, and the other accents don't matter.
+            "spanish" => Self::new(name, "abcdefghijklmnñopqrstuvwxyz".chars(), ACCENTS),
+            "french" => Self::new(name, ENGLISH.chars(), ACCENTS),
+            "german" => Self::new(name, "abcdefghijklmnopqrstuvwxyzäöüß".chars(), &[]),
+            "dutch" => {
+                let mut res = Self::new(name, ENGLISH.chars(), ACCENTS);
+                res.tiles.push(Tile {
+                    text: "ij".to_owned(),
+                    board: 'ĳ',
+                });
+                res.folds.push(('ĳ', "ij"));
+                res
+            }
+            _ => return None,
+        };
+        Some(res)
+    }
+
+    fn new(
+        name: &str,
+        letters: impl Iterator<Item = char>,
+        folds: &[(char, &'static str)],
+    ) -> Self {
+        Self {
+            name: name.to_owned(),
+            tiles: letters
+                .map(|ch| Tile {
+                    text: ch.to_string(),
+                    board: ch,
+                })
+                .collect(),
+            folds: folds.to_vec(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_english(&self) -> bool {
+        self.name == "english"
+    }
+
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    /// The letters of a tile, in lower case.
+    pub fn text(&self, l: Letter) -> &str {
+        &self.tiles[l.i()].text
+    }
+
+    /// Split a word into tiles. Returns None if the word has characters that aren't in the
+    /// alphabet.
+    pub fn letters(&self, word: &str) -> Option<Vec<Letter>> {
+        let mut folded = String::with_capacity(word.len());
+        for ch in word.chars().flat_map(char::to_lowercase) {
+            match self.folds.iter().find(|(from, _)| *from == ch) {
+                Some((_, to)) => folded.push_str(to),
+                None => folded.push(ch),
+            }
+        }
+        let mut res = Vec::with_capacity(folded.len());
+        let mut rest = folded.as_str();
+        while !rest.is_empty() {
+            // The longest tile wins, so Dutch "ij" is one tile.
+            let (i, tile) = self
+                .tiles
+                .iter()
+                .enumerate()
+                .filter(|(_, t)| rest.starts_with(&t.text))
+                .max_by_key(|(_, t)| t.text.len())?;
+            res.push(Letter::for_i(i));
+            rest = &rest[tile.text.len()..];
+        }
+        Some(res)
+    }
+
+    /// The tile for one character of a board file.
+    pub fn board_letter(&self, ch: char) -> Option<Letter> {
+        let ch = ch.to_lowercase().next()?;
+        match self.tiles.iter().position(|t| t.board == ch) {
+            Some(i) => Some(Letter::for_i(i)),
+            None => match self.letters(&ch.to_string())?.as_slice() {
+                [l] => Some(*l),
+                _ => None,
+            },
+        }
+    }
+
+    /// How a tile is written on a board, in upper case if that's still one character. (ß isn't.)
+    pub fn board_text(&self, l: Letter) -> String {
+        self.board_char(l).to_string()
+    }
+
+    /// The character for a tile on a board, like `board_text`.
+    pub fn board_char(&self, l: Letter) -> char {
+        let board = self.tiles[l.i()].board;
+        let mut upper = board.to_uppercase();
+        match (upper.next(), upper.next()) {
+            (Some(ch), None) => ch,
+            _ => board,
+        }
+    }
+
+    /// The Q and U tiles, if this alphabet has them. A Q on a board is "Qu".
+    pub fn qu(&self) -> Option<(Letter, Letter)> {
+        let find = |s: &str| self.tiles.iter().position(|t| t.text == s);
+        Some((Letter::for_i(find("q")?), Letter::for_i(find("u")?)))
+    }
+
+    /// Spell a word from the tiles on a board, where Q is "qu".
+    pub fn board_word(&self, letters: &[Letter]) -> String {
+        let qu = self.qu();
+        let mut res = String::new();
+        for l in letters {
+            res.push_str(self.text(*l));
+            if let Some((q, u)) = qu {
+                if *l == q {
+                    res.push_str(self.text(u));
+                }
+            }
+        }
+        res
+    }
+
+    /// The header for a compiled dictionary, e.g. "{dutch a b c ... z ij/ĳ}".
+    pub fn header(&self) -> String {
+        let tiles: Vec<String> = self
+            .tiles
+            .iter()
+            .map(|t| match t.text.chars().eq(std::iter::once(t.board)) {
+                true => t.text.clone(),
+                false => format!("{}/{}", t.text, t.board),
+            })
+            .collect();
+        format!("{{{} {}}}", self.name, tiles.join(" "))
+    }
+
+    pub fn from_header(header: &str) -> Option<Self> {
+        let mut fields = header.strip_prefix('{')?.strip_suffix('}')?.split(' ');
+        let name = fields.next()?;
+        let mut tiles = vec![];
+        for field in fields {
+            let (text, board) = match field.split_once('/') {
+                Some((text, board)) => (text, board.chars().next()?),
+                None => (field, field.chars().next()?),
+            };
+            tiles.push(Tile {
+                text: text.to_owned(),
+                board,
+            });
+        }
+        // Keep the folds of a known alphabet, as long as it hasn't changed.
+        match Self::named(name) {
+            Some(known) if known.tiles == tiles => Some(known),
+            _ => Some(Self {
+                name: name.to_owned(),
+                tiles,
+                folds: vec![],
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Alphabet;
+    use crate::dictionary::Letter;
+
+    fn spell(a: &Alphabet, word: &str) -> Vec<String> {
+        a.letters(word)
+            .unwrap()
+            .iter()
+            .map(|l| a.text(*l).to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn letters() {
+        let english = Alphabet::english();
+        let cat = vec![Letter::new('c'), Letter::new('a'), Letter::new('t')];
+        assert_eq!(english.letters("Cat"), Some(cat));
+        assert_eq!(english.letters("café"), None);
+
+        let french = Alphabet::named("french").unwrap();
+        assert_eq!(spell(&french, "Cœur"), vec!["c", "o", "e", "u", "r"]);
+        assert_eq!(french.letters("café"), english.letters("cafe"));
+
+        let spanish = Alphabet::named("spanish").unwrap();
+        assert_eq!(spell(&spanish, "año"), vec!["a", "ñ", "o"]);
+        assert_eq!(spanish.letters("año").unwrap()[1], Letter::for_i(14));
+
+        let german = Alphabet::named("german").unwrap();
+        assert_eq!(spell(&german, "Süß"), vec!["s", "ü", "ß"]);
+
+        let dutch = Alphabet::named("dutch").unwrap();
+        assert_eq!(spell(&dutch, "IJs"), vec!["ij", "s"]);
+        assert_eq!(dutch.letters("ĳs"), dutch.letters("ijs"));
+        assert_eq!(dutch.board_letter('Ĳ'), Some(Letter::for_i(26)));
+        assert_eq!(dutch.board_text(Letter::for_i(26)), "Ĳ");
+    }
+
+    #[test]
+    fn headers() {
+        for name in super::NAMES {
+            let a = Alphabet::named(name).unwrap();
+            assert_eq!(Alphabet::from_header(&a.header()), Some(a));
+        }
+        let custom = Alphabet::from_header("{klingon a b ch}").unwrap();
+        assert_eq!(spell(&custom, "chab"), vec!["ch", "a", "b"]);
+        assert_eq!(Alphabet::from_header("dutch a b"), None);
+    }
+}
@@ -0,0 +1,318 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::alphabet::Alphabet;
+use super::dictionary::{Definitions, Dictionary};
+use super::wordsearch::{self, Board, Pos, Scoring, Solution, SolveOptions, WSError};
+
+// The request and response bodies for the web server's endpoints. A change to the /api/v1 ones
+// that would break an existing client gets a new version, with new paths, instead.
+
+/// The version of the bodies under /api/v1. Every response says which version it is.
+pub const VERSION: u32 = 1;
+
+/// How many of the best words a solution lists, without `all_words` or `limit`.
+pub const DEFAULT_LIMIT: usize = 20;
+
+/// A board as a list of rows, or as text with rows separated by newlines or commas.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum BoardInput {
+    Rows(Vec<String>),
+    Text(String),
+}
+
+impl BoardInput {
+    /// The board as text, one line per row.
+    pub fn text(&self) -> String {
+        match self {
+            BoardInput::Rows(rows) => rows.join("\n"),
+            BoardInput::Text(text) => text.trim().replace(',', "\n"),
+        }
+    }
+}
+
+/// The body of `POST /api/v1/boggle/solve`.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SolveRequest {
+    pub board: BoardInput,
+    /// The name of a dictionary from /dicts. Defaults to the server's default dictionary.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dict: Option<String>,
+    /// Defaults to the server's scoring rules.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scoring: Option<Scoring>,
+    /// Raises the scoring rules' minimum length. It can't be less than the board's minimum, which
+    /// is 4 on 5x5 and bigger boards.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_length: Option<usize>,
+    /// Include the path of each word on the board.
+    #[serde(default)]
+    pub paths: bool,
+    /// Include definitions.
+    #[serde(default)]
+    pub definitions: bool,
+    /// List every word, not just the best ones.
+    #[serde(default)]
+    pub all_words: bool,
+    /// How many of the best words to list, without `all_words`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+impl SolveRequest {
+    pub fn new(board: BoardInput) -> Self {
+        Self {
+            board,
+            dict: None,
+            scoring: None,
+            min_length: None,
+            paths: false,
+            definitions: false,
+            all_words: false,
+            limit: None,
+        }
+    }
+
+    /// The request's scoring rules, or else `default`, with its `min_length` for `board`. Words
+    /// score the same points with or without `min_length`; shorter words just don't count.
+    pub fn rules(&self, default: &Scoring, board: &Board) -> Result<Scoring, RequestError> {
+        let mut scoring = self.scoring.clone().unwrap_or_else(|| default.clone());
+        if scoring.points.is_empty() {
+            return Err(RequestError::Scoring("points can't be empty".to_owned()));
+        }
+        if let Some(min_length) = self.min_length {
+            let least = scoring.min_length.max(board.min_word_size());
+            if min_length < least {
+                return Err(RequestError::Scoring(format!(
+                    "min_length can't be less than {} on this board",
+                    least
+                )));
+            }
+            // The last entry is for all longer words, so it stays.
+            let shorter = (min_length - scoring.min_length).min(scoring.points.len() - 1);
+            scoring.points.drain(..shorter);
+            scoring.min_length = min_length;
+        }
+        Ok(scoring)
+    }
+
+    /// The request's board, with the tiles of `alphabet`.
+    pub fn board_in(&self, alphabet: &Alphabet) -> Result<Board, RequestError> {
+        wordsearch::boggled_in(&self.board.text(), alphabet).map_err(RequestError::Board)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
+pub struct SolveResponse {
+    pub version: u32,
+    /// The dictionary that was used.
+    pub dict: String,
+    /// The board, one string per row.
+    pub board: Vec<String>,
+    /// The scoring rules that were used.
+    pub scoring: Scoring,
+    /// The totals are for every word, even when only the best ones are listed.
+    pub total_words: usize,
+    pub total_score: u32,
+    /// Best first.
+    pub words: Vec<SolveWord>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
+pub struct SolveWord {
+    pub word: String,
+    pub score: u32,
+    /// The row and column of each letter, from 0.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<Vec<Pos>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub definition: Option<String>,
+}
+
+/// The body of every error response, e.g.
+/// `{"error": "bad_tile", "message": "...", "row": 2, "col": 3}`.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
+pub struct ErrorBody {
+    /// A code that scripts can match on, like "bad_tile" or "unknown_dict".
+    pub error: String,
+    /// For people.
+    pub message: String,
+    /// Where a bad tile is, from 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub row: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub col: Option<usize>,
+    /// Why a banned word isn't allowed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// A loaded dictionary, from /dicts.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
+pub struct DictInfo {
+    pub name: String,
+    pub words: usize,
+    pub definitions: usize,
+    pub default: bool,
+}
+
+/// Why a request can't be answered.
+#[derive(Debug, PartialEq)]
+pub enum RequestError {
+    Board(WSError),
+    Scoring(String),
+}
+
+impl std::fmt::Display for RequestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RequestError::Board(err) => write!(f, "{}", err),
+            RequestError::Scoring(msg) => write!(f, "invalid scoring rules: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for RequestError {}
+
+/// Answer a solve request with the named dictionary. `scoring` is used if the request doesn't
+/// have its own rules.
+pub fn solve(
+    name: &str,
+    dict: &Dictionary,
+    defs: &Definitions,
+    request: &SolveRequest,
+    scoring: &Scoring,
+) -> Result<SolveResponse, RequestError> {
+    let board = request.board_in(&dict.alphabet)?;
+    let scoring = request.rules(scoring, &board)?;
+    let opts = SolveOptions {
+        defs: Some(defs),
+        scoring: Some(&scoring),
+        ..Default::default()
+    };
+    let solution = wordsearch::solve(dict, &board, &opts);
+    Ok(respond(name, request, scoring, &solution))
+}
+
+/// The response to a request, from a solution with every word and its definition, found with
+/// `scoring`. A server can keep the solution and answer other requests for the same board with it.
+pub fn respond(
+    name: &str,
+    request: &SolveRequest,
+    scoring: Scoring,
+    solution: &Solution,
+) -> SolveResponse {
+    let limit = match request.all_words {
+        true => usize::MAX,
+        false => request.limit.unwrap_or(DEFAULT_LIMIT),
+    };
+    let words = solution
+        .words
+        .iter()
+        .take(limit)
+        .map(|w| SolveWord {
+            word: w.word.clone(),
+            score: w.score,
+            path: request.paths.then(|| w.path.clone()),
+            definition: w.def.clone().filter(|_| request.definitions),
+        })
+        .collect();
+    SolveResponse {
+        version: VERSION,
+        dict: name.to_owned(),
+        board: solution.board.clone(),
+        scoring,
+        total_words: solution.total_words,
+        total_score: solution.total_score,
+        words,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BoardInput, RequestError, SolveRequest};
+    use crate::dictionary::{build_dictionary, Definitions};
+    use crate::wordsearch::{Scoring, WSError};
+
+    #[test]
+    fn solve() {
+        let dict = build_dictionary(vec!["cat", "cats", "act"]);
+        let defs = Definitions::from([("cat".to_owned(), "mrow".to_owned())]);
+        let scoring = Scoring::default();
+
+        let request: SolveRequest =
+            serde_json::from_str(r#"{"board": "cats,xxxx,xxxx,xxxx", "definitions": true}"#)
+                .unwrap();
+        let res = super::solve("test", &dict, &defs, &request, &scoring).unwrap();
+        assert_eq!(res.version, 1);
+        assert_eq!(res.board, vec!["CATS", "XXXX", "XXXX", "XXXX"]);
+        assert_eq!((res.total_words, res.total_score), (2, 2));
+        assert_eq!(res.words[1].definition.as_deref(), Some("mrow"));
+        assert_eq!(res.words[1].path, None);
+
+        let mut request = SolveRequest::new(BoardInput::Rows(
+            ["cats", "xxxx", "xxxx", "xxxx"].map(String::from).to_vec(),
+        ));
+        request.min_length = Some(4);
+        request.paths = true;
+        let res = super::solve("test", &dict, &defs, &request, &scoring).unwrap();
+        assert_eq!(res.scoring.min_length, 4);
+        assert_eq!(res.total_words, 1);
+        assert_eq!(
+            res.words[0].path,
+            Some(vec![(0, 0), (0, 1), (0, 2), (0, 3)])
+        );
+        assert_eq!(res.words[0].definition, None);
+
+        request.board = BoardInput::Text("cats".to_owned());
+        assert_eq!(
+            super::solve("test", &dict, &defs, &request, &scoring),
+            Err(RequestError::Board(WSError::WrongTileCount(4)))
+        );
+        let bad = serde_json::from_str::<SolveRequest>(r#"{"board": "cats", "path": true}"#);
+        assert!(bad.is_err());
+    }
+
+    #[test]
+    fn min_length() {
+        let dict = build_dictionary(vec!["act", "fact", "facts"]);
+        let defs = Definitions::new();
+        let scoring = Scoring::default();
+
+        let mut request = SolveRequest::new(BoardInput::Text("fact,xxxs,xxxx,xxxx".to_owned()));
+        request.min_length = Some(4);
+        let res = super::solve("test", &dict, &defs, &request, &scoring).unwrap();
+        let words: Vec<(&str, u32)> = res
+            .words
+            .iter()
+            .map(|w| (w.word.as_str(), w.score))
+            .collect();
+        // A 5-letter word still scores 2.
+        assert_eq!(words, vec![("facts", 2), ("fact", 1)]);
+        assert_eq!(res.scoring.min_length, 4);
+        assert_eq!(res.scoring.points, vec![1, 2, 3, 5, 11]);
+        assert_eq!(res.scoring.score("facts"), 2);
+
+        request.min_length = Some(20);
+        let res = super::solve("test", &dict, &defs, &request, &scoring).unwrap();
+        assert_eq!(
+            (res.total_words, res.scoring.points.as_slice()),
+            (0, &[11][..])
+        );
+
+        request.min_length = Some(2);
+        assert!(matches!(
+            super::solve("test", &dict, &defs, &request, &scoring),
+            Err(RequestError::Scoring(_))
+        ));
+        // 5x5 boards need at least 4 letters.
+        request.board = BoardInput::Text("facts,xxxxx,xxxxx,xxxxx,xxxxx".to_owned());
+        request.min_length = Some(3);
+        assert!(matches!(
+            super::solve("test", &dict, &defs, &request, &scoring),
+            Err(RequestError::Scoring(_))
+        ));
+    }
+}
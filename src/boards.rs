@@ -0,0 +1,93 @@
+use std::error::Error;
+use std::io::Read;
+
+// Boards can come from a file, from stdin (when the path is "-"), or from the command line as
+// comma-separated rows like "abcde,fghij,klmno,pqrst,uvwxy".
+
+/// Read one board from a path or from letters given on the command line.
+pub fn read_board(path: Option<&str>, letters: Option<&str>) -> Result<String, Box<dyn Error>> {
+    match (path, letters) {
+        (_, Some(letters)) => Ok(from_letters(letters)),
+        (Some(path), None) => read_path(path),
+        (None, None) => Err("give a board file, \"-\" for stdin, or --letters".into()),
+    }
+}
+
+/// Read all of the boards in a file or stdin. Boards are separated by blank lines. Each board is
+/// named after the path, with a number if there's more than one.
+pub fn read_boards(path: &str) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+    let name = match path {
+        "-" => "stdin",
+        path => path,
+    };
+    let boards = split(&read_path(path)?);
+    Ok(match boards.len() {
+        1 => boards.into_iter().map(|b| (name.to_owned(), b)).collect(),
+        _ => boards
+            .into_iter()
+            .enumerate()
+            .map(|(i, b)| (format!("{}:{}", name, i + 1), b))
+            .collect(),
+    })
+}
+
+fn read_path(path: &str) -> Result<String, Box<dyn Error>> {
+    match path {
+        "-" => {
+            let mut s = String::new();
+            std::io::stdin().read_to_string(&mut s)?;
+            Ok(s)
+        }
+        path => Ok(std::fs::read_to_string(path)?),
+    }
+}
+
+/// Turn "abcde,fghij,..." into one line per row.
+pub fn from_letters(letters: &str) -> String {
+    letters
+        .split(|ch: char| ch == ',' || ch.is_whitespace())
+        .filter(|row| !row.is_empty())
+        .map(|row| row.to_owned() + "\n")
+        .collect()
+}
+
+/// Split text with several boards separated by blank lines.
+pub fn split(text: &str) -> Vec<String> {
+    let mut res = vec![];
+    let mut board = String::new();
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            if !board.is_empty() {
+                res.push(std::mem::take(&mut board));
+            }
+        } else {
+            board.push_str(line.trim());
+            board.push('\n');
+        }
+    }
+    if !board.is_empty() {
+        res.push(board);
+    }
+    res
+}
+
+#[cfg(test)]
+mod test {
+    use super::{from_letters, split};
+
+    #[test]
+    fn letters() {
+        assert_eq!(
+            from_letters("abcd,efgh, ijkl,mnop"),
+            "abcd\nefgh\nijkl\nmnop\n"
+        );
+    }
+
+    #[test]
+    fn split_boards() {
+        assert_eq!(
+            split("\nabcd\nefgh\n\n  \nijkl\nmnop\n\n"),
+            vec!["abcd\nefgh\n", "ijkl\nmnop\n"]
+        );
+    }
+}
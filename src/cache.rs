@@ -0,0 +1,153 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::sync::Arc;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+// A least-recently-used cache that holds at most `max_bytes` of entries. The caller says how big
+// each entry is, since only it knows what's inside.
+
+pub struct Lru<K, V> {
+    entries: HashMap<K, Entry<V>>,
+    // Keys by when they were last used, oldest first.
+    order: BTreeMap<u64, K>,
+    clock: u64,
+    stats: CacheStats,
+}
+
+struct Entry<V> {
+    value: Arc<V>,
+    bytes: usize,
+    used: u64,
+}
+
+/// How the cache is doing, from /cache.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, JsonSchema)]
+pub struct CacheStats {
+    pub entries: usize,
+    /// The estimated size of the entries.
+    pub bytes: usize,
+    pub max_bytes: usize,
+    pub hits: u64,
+    pub misses: u64,
+    /// Entries that were dropped to make room for newer ones.
+    pub evictions: u64,
+}
+
+impl<K: Clone + Eq + Hash, V> Lru<K, V> {
+    /// A cache that holds nothing with `max_bytes` 0.
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            clock: 0,
+            stats: CacheStats {
+                max_bytes,
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn get(&mut self, key: &K) -> Option<Arc<V>> {
+        let Some(entry) = self.entries.get_mut(key) else {
+            self.stats.misses += 1;
+            return None;
+        };
+        self.stats.hits += 1;
+        self.clock += 1;
+        let key = self
+            .order
+            .remove(&entry.used)
+            .expect("every entry is in order");
+        self.order.insert(self.clock, key);
+        entry.used = self.clock;
+        Some(entry.value.clone())
+    }
+
+    /// Add an entry, dropping the least recently used ones until it fits. An entry that's bigger
+    /// than the whole cache isn't added.
+    pub fn insert(&mut self, key: K, value: Arc<V>, bytes: usize) {
+        if bytes > self.stats.max_bytes {
+            return;
+        }
+        self.remove(&key);
+        while self.stats.bytes + bytes > self.stats.max_bytes {
+            let (_, oldest) = self
+                .order
+                .pop_first()
+                .expect("bytes are only used by entries");
+            self.remove(&oldest);
+            self.stats.evictions += 1;
+        }
+        self.clock += 1;
+        self.order.insert(self.clock, key.clone());
+        self.entries.insert(
+            key,
+            Entry {
+                value,
+                bytes,
+                used: self.clock,
+            },
+        );
+        self.stats.entries += 1;
+        self.stats.bytes += bytes;
+    }
+
+    /// Drop every entry. The hit and miss counts are kept.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+        self.stats.entries = 0;
+        self.stats.bytes = 0;
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats.clone()
+    }
+
+    fn remove(&mut self, key: &K) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.order.remove(&entry.used);
+            self.stats.entries -= 1;
+            self.stats.bytes -= entry.bytes;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::Lru;
+
+    #[test]
+    fn lru() {
+        let mut cache = Lru::new(10);
+        cache.insert("a", Arc::new(1), 4);
+        cache.insert("b", Arc::new(2), 4);
+        assert_eq!(cache.get(&"a").as_deref(), Some(&1));
+        // "b" is the least recently used now.
+        cache.insert("c", Arc::new(3), 4);
+        assert_eq!(cache.get(&"b"), None);
+        assert_eq!(cache.get(&"c").as_deref(), Some(&3));
+        // Too big to ever fit.
+        cache.insert("d", Arc::new(4), 11);
+        assert_eq!(cache.get(&"d"), None);
+        // Replacing an entry doesn't count it twice.
+        cache.insert("a", Arc::new(5), 6);
+        assert_eq!(cache.get(&"a").as_deref(), Some(&5));
+
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.bytes, stats.max_bytes), (2, 10, 10));
+        assert_eq!((stats.hits, stats.misses, stats.evictions), (3, 2, 1));
+
+        cache.clear();
+        assert_eq!(cache.get(&"a"), None);
+        assert_eq!((cache.stats().entries, cache.stats().hits), (0, 3));
+
+        let mut off = Lru::new(0);
+        off.insert("a", Arc::new(1), 1);
+        assert_eq!(off.get(&"a"), None);
+    }
+}
@@ -0,0 +1,181 @@
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+
+use super::api::{DictInfo, ErrorBody, SolveRequest, SolveResponse};
+
+// A small blocking client for the web server's JSON endpoints. It speaks plain HTTP, which is all
+// the server does.
+
+/// A connection to a server, e.g. `Client::new("http://127.0.0.1:8000")`.
+pub struct Client {
+    agent: ureq::Agent,
+    base: String,
+}
+
+impl Client {
+    pub fn new(url: &str) -> Result<Self, ClientError> {
+        match url.strip_prefix("http://") {
+            Some(rest) if !rest.starts_with('/') && !rest.is_empty() => (),
+            _ => return Err(ClientError::BadUrl(url.to_owned())),
+        }
+        Ok(Self {
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(60))
+                .build(),
+            base: url.trim_end_matches('/').to_owned(),
+        })
+    }
+
+    /// Find the words on a board with `POST /api/v1/boggle/solve`.
+    ///
+    /// ```no_run
+    /// use boggle_ocr::api::{BoardInput, SolveRequest};
+    /// use boggle_ocr::client::Client;
+    ///
+    /// let client = Client::new("http://127.0.0.1:8000")?;
+    /// let board = BoardInput::Text("abcde,fghij,klmno,pqrst,uvwxy".to_owned());
+    /// let solution = client.solve(&SolveRequest::new(board))?;
+    /// println!("{} words, {} points", solution.total_words, solution.total_score);
+    /// # Ok::<(), boggle_ocr::client::ClientError>(())
+    /// ```
+    pub fn solve(&self, request: &SolveRequest) -> Result<SolveResponse, ClientError> {
+        let body = serde_json::to_vec(request).map_err(ClientError::Json)?;
+        self.call("POST", "/api/v1/boggle/solve", Some(&body))
+    }
+
+    /// The loaded dictionaries, default first.
+    pub fn dicts(&self) -> Result<Vec<DictInfo>, ClientError> {
+        self.call("GET", "/dicts", None)
+    }
+
+    fn call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<&[u8]>,
+    ) -> Result<T, ClientError> {
+        let request = self
+            .agent
+            .request(method, &format!("{}{}", self.base, path))
+            .set("Accept", "application/json");
+        let res = match body {
+            Some(body) => request
+                .set("Content-Type", "application/json")
+                .send_bytes(body),
+            None => request.call(),
+        };
+        match res {
+            Ok(res) => serde_json::from_reader(res.into_reader()).map_err(ClientError::Json),
+            Err(ureq::Error::Status(status, res)) => {
+                Err(match serde_json::from_reader(res.into_reader()) {
+                    Ok(body) => ClientError::Api { status, body },
+                    Err(_) => ClientError::Status(status),
+                })
+            }
+            Err(ureq::Error::Transport(err)) => Err(ClientError::Transport(Box::new(err))),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ClientError {
+    /// Only http:// URLs work.
+    BadUrl(String),
+    /// The server couldn't be reached, or the connection failed.
+    Transport(Box<ureq::Transport>),
+    /// The server said what went wrong.
+    Api {
+        status: u16,
+        body: ErrorBody,
+    },
+    /// The server failed without saying why.
+    Status(u16),
+    Json(serde_json::Error),
+}
+
+impl std::fmt::Display for ClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClientError::BadUrl(url) => write!(f, "{} isn't an http:// URL", url),
+            ClientError::Transport(err) => write!(f, "{}", err),
+            ClientError::Api { status, body } => write!(f, "{} ({})", body.message, status),
+            ClientError::Status(status) => write!(f, "the server returned {}", status),
+            ClientError::Json(err) => write!(f, "invalid JSON: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+#[cfg(test)]
+mod test {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    use super::{Client, ClientError};
+    use crate::api::{BoardInput, SolveRequest};
+
+    // Answer one request with `response` and return what was asked.
+    fn serve_once(response: &'static str) -> (String, std::thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/boggle/", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = vec![];
+            let mut buf = [0; 4096];
+            // The headers, then as much body as they say there is.
+            while !complete(&request) {
+                let n = stream.read(&mut buf).unwrap();
+                assert!(n > 0, "the request ended early");
+                request.extend_from_slice(&buf[..n]);
+            }
+            stream.write_all(response.as_bytes()).unwrap();
+            String::from_utf8_lossy(&request).into_owned()
+        });
+        (url, handle)
+    }
+
+    fn complete(request: &[u8]) -> bool {
+        let text = String::from_utf8_lossy(request);
+        let Some((head, body)) = text.split_once("\r\n\r\n") else {
+            return false;
+        };
+        let length = head
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+            .map_or(0, |(_, value)| value.trim().parse().unwrap());
+        body.len() >= length
+    }
+
+    #[test]
+    fn solve() {
+        let (url, server) = serve_once(concat!(
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n",
+            "10\r\n{\"version\":1,\"di\r\n",
+            "6f\r\nct\":\"owl2\",\"board\":[\"CATS\"],\"scoring\":{\"min_length\":3,\"points\":[1]},",
+            "\"total_words\":1,\"total_score\":1,\"words\":[]}\r\n0\r\n\r\n",
+        ));
+        let client = Client::new(&url).unwrap();
+        let request = SolveRequest::new(BoardInput::Text("cats".to_owned()));
+        let res = client.solve(&request).unwrap();
+        assert_eq!((res.dict.as_str(), res.total_words), ("owl2", 1));
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /boggle/api/v1/boggle/solve HTTP/1.1\r\n"));
+        assert!(request.ends_with("\r\n\r\n{\"board\":\"cats\",\"paths\":false,\"definitions\":false,\"all_words\":false}"));
+
+        let (url, _) = serve_once(concat!(
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 58\r\n\r\n",
+            "{\"error\":\"unknown_dict\",\"message\":\"no dictionary named x\"}",
+        ));
+        match Client::new(&url).unwrap().dicts() {
+            Err(ClientError::Api { status, body }) => {
+                assert_eq!((status, body.error.as_str()), (404, "unknown_dict"))
+            }
+            res => panic!("expected an error, got {:?}", res),
+        }
+
+        assert!(Client::new("https://example.com").is_err());
+    }
+}
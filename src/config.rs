@@ -0,0 +1,650 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+use toml::Spanned;
+
+// Settings files, in TOML. Every setting is in a table:
+//
+//     # where the words are
+//     [dictionary]
+//     dict = "/data/owl2.dict"
+//     search_path = ["/data", "words"]
+//
+// Keys are looked up with their table, like "dictionary.dict". The user's file is read first, then
+// the project's boggle-ocr.toml, which wins. Flags win over both.
+
+/// Picks a dictionary, over the config files.
+pub const DICT_VAR: &str = "BOGGLE_OCR_DICT";
+
+/// The project's config file, in the current directory or the nearest parent that has one.
+pub const PROJECT_FILE: &str = "boggle-ocr.toml";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    String(String),
+    Integer(i64),
+    Array(Vec<Value>),
+}
+
+impl Value {
+    fn from_toml(value: toml::Value) -> Option<Self> {
+        match value {
+            toml::Value::String(s) => Some(Value::String(s)),
+            toml::Value::Integer(i) => Some(Value::Integer(i)),
+            toml::Value::Array(items) => items
+                .into_iter()
+                .map(Value::from_toml)
+                .collect::<Option<Vec<Value>>>()
+                .map(Value::Array),
+            _ => None,
+        }
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::String(s)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(i: i64) -> Self {
+        Value::Integer(i)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(items: Vec<T>) -> Self {
+        Value::Array(items.into_iter().map(Into::into).collect())
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::String(s) => write!(f, "{:?}", s),
+            Value::Integer(i) => write!(f, "{}", i),
+            Value::Array(items) => {
+                let items: Vec<String> = items.iter().map(Value::to_string).collect();
+                write!(f, "[{}]", items.join(", "))
+            }
+        }
+    }
+}
+
+/// What a setting holds. Paths are relative to the file they're in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Kind {
+    String,
+    Path,
+    Paths,
+    Integer,
+    Integers,
+}
+
+/// A setting that can go in a config file.
+pub struct Key {
+    pub name: &'static str,
+    pub kind: Kind,
+    /// The value without a config file, as TOML.
+    pub default: Option<&'static str>,
+    /// An environment variable that overrides the config files.
+    pub env: Option<&'static str>,
+    pub help: &'static str,
+}
+
+pub const KEYS: &[Key] = &[
+    Key {
+        name: "dictionary.dict",
+        kind: Kind::Path,
+        default: None,
+        env: Some(DICT_VAR),
+        help: "The JSON or compiled dictionary. Otherwise cached.dict or DICT.json on the search path.",
+    },
+    Key {
+        name: "dictionary.defs",
+        kind: Kind::Path,
+        default: None,
+        env: None,
+        help: "Compiled definitions or a JSON dictionary. Otherwise cached.defs or DICT.json on the search path.",
+    },
+    Key {
+        name: "dictionary.freq",
+        kind: Kind::Path,
+        default: None,
+        env: None,
+        help: "Word frequencies. Otherwise cached.freq, if it's on the search path.",
+    },
+    Key {
+        name: "dictionary.search_path",
+        kind: Kind::Paths,
+        default: None,
+        env: None,
+        help: "More directories to look for dictionaries in.",
+    },
+    Key {
+        name: "scoring.min_length",
+        kind: Kind::Integer,
+        default: Some("3"),
+        env: None,
+        help: "Shorter words don't count.",
+    },
+    Key {
+        name: "scoring.points",
+        kind: Kind::Integers,
+        default: Some("[1, 1, 2, 3, 5, 11]"),
+        env: None,
+        help: "Points for words of min_length letters, then one letter longer, and so on. The last is for all longer words.",
+    },
+    Key {
+        name: "board.size",
+        kind: Kind::Integer,
+        default: Some("5"),
+        env: None,
+        help: "Rows and columns on boards that optimize makes.",
+    },
+    Key {
+        name: "board.dice",
+        kind: Kind::String,
+        default: Some("\"big\""),
+        env: None,
+        help: "The dice for generate and play: classic, new, big, or super-big. Also used by optimize when it's set.",
+    },
+    Key {
+        name: "output.format",
+        kind: Kind::String,
+        default: Some("\"text\""),
+        env: None,
+        help: "text, json, csv, or tsv.",
+    },
+    Key {
+        name: "output.limit",
+        kind: Kind::Integer,
+        default: Some("20"),
+        env: None,
+        help: "How many of the best words boggle shows without --show-all.",
+    },
+    Key {
+        name: "server.addr",
+        kind: Kind::String,
+        default: Some("\"127.0.0.1:0\""),
+        env: None,
+        help: "Where the web server listens.",
+    },
+    Key {
+        name: "server.assets",
+        kind: Kind::Path,
+        default: Some("\"assets\""),
+        env: None,
+        help: "The web server's static files.",
+    },
+    Key {
+        name: "server.cache_mb",
+        kind: Kind::Integer,
+        default: Some("64"),
+        env: None,
+        help: "How much memory the web server uses to remember solved boards, in megabytes.",
+    },
+];
+
+fn key(name: &str) -> Option<&'static Key> {
+    KEYS.iter().find(|key| key.name == name)
+}
+
+// What a config file can hold. Each value keeps where it is in the file, for errors about it.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct File {
+    dictionary: DictionaryTable,
+    scoring: ScoringTable,
+    board: BoardTable,
+    output: OutputTable,
+    server: ServerTable,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct DictionaryTable {
+    dict: Option<Spanned<String>>,
+    defs: Option<Spanned<String>>,
+    freq: Option<Spanned<String>>,
+    search_path: Option<Spanned<Vec<String>>>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ScoringTable {
+    min_length: Option<Spanned<i64>>,
+    points: Option<Spanned<Vec<i64>>>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct BoardTable {
+    size: Option<Spanned<i64>>,
+    dice: Option<Spanned<String>>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct OutputTable {
+    format: Option<Spanned<String>>,
+    limit: Option<Spanned<i64>>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ServerTable {
+    addr: Option<Spanned<String>>,
+    assets: Option<Spanned<String>>,
+    cache_mb: Option<Spanned<i64>>,
+}
+
+// A value and where it is in the file, in bytes.
+type Located = (Value, Range<usize>);
+
+impl File {
+    // Every setting in the file, by name, with where its value is.
+    fn values(self) -> Vec<(&'static str, Option<Located>)> {
+        fn value<T: Into<Value>>(v: Option<Spanned<T>>) -> Option<Located> {
+            v.map(|v| {
+                let span = v.span();
+                (v.into_inner().into(), span)
+            })
+        }
+        let File {
+            dictionary: d,
+            scoring,
+            board,
+            output,
+            server,
+        } = self;
+        vec![
+            ("dictionary.dict", value(d.dict)),
+            ("dictionary.defs", value(d.defs)),
+            ("dictionary.freq", value(d.freq)),
+            ("dictionary.search_path", value(d.search_path)),
+            ("scoring.min_length", value(scoring.min_length)),
+            ("scoring.points", value(scoring.points)),
+            ("board.size", value(board.size)),
+            ("board.dice", value(board.dice)),
+            ("output.format", value(output.format)),
+            ("output.limit", value(output.limit)),
+            ("server.addr", value(server.addr)),
+            ("server.assets", value(server.assets)),
+            ("server.cache_mb", value(server.cache_mb)),
+        ]
+    }
+}
+
+/// One config file.
+#[derive(Debug, Default)]
+pub struct Config {
+    path: PathBuf,
+    values: BTreeMap<String, (Value, usize)>,
+}
+
+impl Config {
+    /// The user's config file, $XDG_CONFIG_HOME/boggle-ocr/config.toml.
+    pub fn user_path() -> Option<PathBuf> {
+        let dir = match std::env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+        };
+        Some(dir.join("boggle-ocr").join("config.toml"))
+    }
+
+    /// Read the user's config file. It's fine if there isn't one.
+    pub fn user() -> Result<Option<Self>, ConfigError> {
+        match Self::user_path() {
+            Some(path) => Self::open(path),
+            None => Ok(None),
+        }
+    }
+
+    /// Read the project's config file, if there's one here or in a parent directory.
+    pub fn project() -> Result<Option<Self>, ConfigError> {
+        let cwd = match std::env::current_dir() {
+            Ok(cwd) => cwd,
+            Err(_) => return Ok(None),
+        };
+        for dir in cwd.ancestors() {
+            if let Some(config) = Self::open(dir.join(PROJECT_FILE))? {
+                return Ok(Some(config));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Read a config file, or None if it doesn't exist.
+    pub fn open(path: impl AsRef<Path>) -> Result<Option<Self>, ConfigError> {
+        let path = path.as_ref();
+        match std::fs::read_to_string(path) {
+            Ok(text) => Self::parse(path, &text).map(Some),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(ConfigError::new(path, 0, err.to_string())),
+        }
+    }
+
+    /// Parse a config file. Unknown settings and values of the wrong type are errors.
+    pub fn parse(path: impl AsRef<Path>, text: &str) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let line = |span: Option<Range<usize>>| match span {
+            Some(span) => text[..span.start.min(text.len())].matches('\n').count() + 1,
+            None => 0,
+        };
+        let file: File = toml::from_str(text)
+            .map_err(|err| ConfigError::new(path, line(err.span()), err.message().to_owned()))?;
+        let values = file
+            .values()
+            .into_iter()
+            .filter_map(|(name, value)| {
+                let (value, span) = value?;
+                Some((name.to_owned(), (value, line(Some(span)))))
+            })
+            .collect();
+        Ok(Self {
+            path: path.to_owned(),
+            values,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.values.get(key).map(|(value, _)| value)
+    }
+
+    /// A path from the config file. Relative paths are relative to the file.
+    pub fn resolve(&self, path: &str) -> PathBuf {
+        match self.path.parent() {
+            Some(dir) => dir.join(path),
+            None => PathBuf::from(path),
+        }
+    }
+
+    // The value with its paths resolved.
+    fn resolved(&self, kind: Kind, value: &Value) -> Value {
+        let resolve = |v: &Value| match v {
+            Value::String(path) => Value::String(self.resolve(path).display().to_string()),
+            v => v.clone(),
+        };
+        match (kind, value) {
+            (Kind::Path, v) => resolve(v),
+            (Kind::Paths, Value::Array(items)) => Value::Array(items.iter().map(resolve).collect()),
+            (_, v) => v.clone(),
+        }
+    }
+}
+
+/// Where a setting's value comes from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Source {
+    Default,
+    Env(&'static str),
+    File(PathBuf),
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::Env(var) => write!(f, "${}", var),
+            Source::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// The settings from the environment and every config file.
+#[derive(Debug, Default)]
+pub struct Settings {
+    // Later files win.
+    files: Vec<Config>,
+    env: BTreeMap<&'static str, (Value, &'static str)>,
+}
+
+impl Settings {
+    /// Read the user's and the project's config files.
+    pub fn load() -> Result<Self, ConfigError> {
+        let mut files = vec![];
+        files.extend(Config::user()?);
+        files.extend(Config::project()?);
+        Ok(Self::new(files, |var| std::env::var(var).ok()))
+    }
+
+    /// The settings from `load`, which reads the files the first time they're needed. Commands
+    /// that don't use any settings never read them, so a bad config file doesn't stop them. The
+    /// library itself never calls this.
+    pub fn shared() -> Result<&'static Self, ConfigError> {
+        static SHARED: OnceLock<Result<Settings, ConfigError>> = OnceLock::new();
+        SHARED
+            .get_or_init(Self::load)
+            .as_ref()
+            .map_err(Clone::clone)
+    }
+
+    /// Like `load`, but a file with an error is left out instead. Also returns the errors.
+    pub fn load_readable() -> (Self, Vec<ConfigError>) {
+        let mut files = vec![];
+        let mut errors = vec![];
+        for config in [Config::user(), Config::project()] {
+            match config {
+                Ok(config) => files.extend(config),
+                Err(err) => errors.push(err),
+            }
+        }
+        (Self::new(files, |var| std::env::var(var).ok()), errors)
+    }
+
+    /// Settings from these files, in order, and the environment variables from `var`.
+    pub fn new(files: Vec<Config>, var: impl Fn(&str) -> Option<String>) -> Self {
+        let env = KEYS
+            .iter()
+            .filter_map(|key| {
+                let name = key.env?;
+                let value = var(name).filter(|v| !v.is_empty())?;
+                Some((key.name, (Value::String(value), name)))
+            })
+            .collect();
+        Self { files, env }
+    }
+
+    /// The config files that were read.
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.files.iter().map(Config::path)
+    }
+
+    /// The effective value of a setting, with its paths resolved, and where it comes from.
+    pub fn get(&self, name: &str) -> Option<(Value, Source)> {
+        let key = key(name)?;
+        if let Some((value, var)) = self.env.get(name) {
+            return Some((value.clone(), Source::Env(var)));
+        }
+        for config in self.files.iter().rev() {
+            if let Some(value) = config.get(name) {
+                let source = Source::File(config.path().to_owned());
+                return Some((config.resolved(key.kind, value), source));
+            }
+        }
+        let default = parse_default(key.default?).expect("invalid default setting");
+        Some((default, Source::Default))
+    }
+
+    pub fn string(&self, name: &str) -> Option<String> {
+        match self.get(name) {
+            Some((Value::String(s), _)) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn integer(&self, name: &str) -> Option<i64> {
+        match self.get(name) {
+            Some((Value::Integer(i), _)) => Some(i),
+            _ => None,
+        }
+    }
+
+    pub fn path(&self, name: &str) -> Option<(PathBuf, Source)> {
+        match self.get(name) {
+            Some((Value::String(s), source)) => Some((PathBuf::from(s), source)),
+            _ => None,
+        }
+    }
+
+    pub fn paths(&self, name: &str) -> Option<(Vec<PathBuf>, Source)> {
+        let (items, source) = match self.get(name) {
+            Some((Value::Array(items), source)) => (items, source),
+            _ => return None,
+        };
+        let paths = items
+            .into_iter()
+            .filter_map(|v| match v {
+                Value::String(s) => Some(PathBuf::from(s)),
+                _ => None,
+            })
+            .collect();
+        Some((paths, source))
+    }
+
+    pub fn integers(&self, name: &str) -> Option<Vec<i64>> {
+        match self.get(name) {
+            Some((Value::Array(items), _)) => items
+                .into_iter()
+                .map(|v| match v {
+                    Value::Integer(i) => Some(i),
+                    _ => None,
+                })
+                .collect(),
+            _ => None,
+        }
+    }
+
+    /// An error about the value of a setting, pointing at where the value came from.
+    pub fn error(&self, name: &str, msg: impl Display) -> ConfigError {
+        let msg = format!("{}: {}", name, msg);
+        if let Some((_, var)) = self.env.get(name) {
+            return ConfigError::new(Path::new(&format!("${}", var)), 0, msg);
+        }
+        for config in self.files.iter().rev() {
+            if let Some((_, line)) = config.values.get(name) {
+                return ConfigError::new(&config.path, *line, msg);
+            }
+        }
+        ConfigError::new(Path::new("default settings"), 0, msg)
+    }
+}
+
+// A default from KEYS, which is written as TOML.
+fn parse_default(s: &str) -> Option<Value> {
+    let mut table: toml::Table = format!("value = {}", s).parse().ok()?;
+    Value::from_toml(table.remove("value")?)
+}
+
+#[derive(Clone, Debug)]
+pub struct ConfigError {
+    path: PathBuf,
+    line: usize,
+    msg: String,
+}
+
+impl ConfigError {
+    fn new(path: &Path, line: usize, msg: String) -> Self {
+        Self {
+            path: path.to_owned(),
+            line,
+            msg,
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.line {
+            0 => write!(fmt, "{}: {}", self.path.display(), self.msg),
+            line => write!(fmt, "{}:{}: {}", self.path.display(), line, self.msg),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::{Config, Settings, Source, Value, DICT_VAR};
+
+    #[test]
+    fn parse() {
+        let config = Config::parse(
+            "/etc/boggle-ocr/config.toml",
+            "# comment\n[dictionary]\ndict = '/data/a#b.dict' # comment\nsearch_path = [\n  \"/data\",\n  \"a,b\",\n]\n\n[output]\nlimit = 1_000\n",
+        )
+        .unwrap();
+        let s = |s: &str| Value::String(s.to_owned());
+        assert_eq!(config.get("output.limit"), Some(&Value::Integer(1000)));
+        assert_eq!(config.get("dictionary.dict"), Some(&s("/data/a#b.dict")));
+        assert_eq!(
+            config.get("dictionary.search_path"),
+            Some(&Value::Array(vec![s("/data"), s("a,b")]))
+        );
+        assert_eq!(
+            config.resolve("words"),
+            std::path::Path::new("/etc/boggle-ocr/words")
+        );
+        assert_eq!(config.resolve("/data"), std::path::Path::new("/data"));
+
+        let bad = |text: &str| Config::parse("c.toml", text).unwrap_err().to_string();
+        assert!(bad("[dictionary]\ndict = /data\n").starts_with("c.toml:2: "));
+        assert!(bad("[output]\nformat = \"json\"\nlimt = 5\n")
+            .starts_with("c.toml:3: unknown field `limt`"));
+        assert!(bad("[scoring]\npoints = [1, \"2\"]\n").starts_with("c.toml:2: "));
+        assert!(bad("top = 1\n").starts_with("c.toml:1: unknown field `top`"));
+    }
+
+    #[test]
+    fn settings() {
+        let user = Config::parse(
+            "/home/me/.config/boggle-ocr/config.toml",
+            "[dictionary]\ndict = \"owl2.dict\"\n[output]\nlimit = 50\nformat = \"json\"\n",
+        )
+        .unwrap();
+        let project = Config::parse("/src/game/boggle-ocr.toml", "[output]\nlimit = 10\n").unwrap();
+        let settings = Settings::new(vec![user, project], |_| None);
+        assert_eq!(settings.integer("output.limit"), Some(10));
+        assert_eq!(settings.string("output.format"), Some("json".to_owned()));
+        assert_eq!(
+            settings.path("dictionary.dict"),
+            Some((
+                PathBuf::from("/home/me/.config/boggle-ocr/owl2.dict"),
+                Source::File(PathBuf::from("/home/me/.config/boggle-ocr/config.toml"))
+            ))
+        );
+        assert_eq!(
+            settings.get("board.size"),
+            Some((Value::Integer(5), Source::Default))
+        );
+        assert_eq!(
+            settings.integers("scoring.points"),
+            Some(vec![1, 1, 2, 3, 5, 11])
+        );
+        assert_eq!(settings.get("dictionary.defs"), None);
+        assert_eq!(
+            settings.error("output.limit", "too big").to_string(),
+            "/src/game/boggle-ocr.toml:2: output.limit: too big"
+        );
+
+        let env = |var: &str| (var == DICT_VAR).then(|| "my.dict".to_owned());
+        let settings = Settings::new(vec![], env);
+        assert_eq!(
+            settings.path("dictionary.dict"),
+            Some((PathBuf::from("my.dict"), Source::Env(DICT_VAR)))
+        );
+    }
+}
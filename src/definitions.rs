@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::Path;
+
+// Definitions come from the JSON dictionary, or from a compiled definitions file that is much
+// faster to load. The compiled file is:
+//
+//     MAGIC
+//     count            u32, little endian
+//     offsets          count + 1 u32s, where record i is data[offsets[i]..offsets[i + 1]]
+//     data             records of "word\0definition", sorted by word
+//
+// Only the file's bytes are kept in memory. A definition is decoded when it's looked up.
+
+const MAGIC: &[u8] = b"boggle-ocr defs 1\n";
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Definitions(Store);
+
+#[derive(Clone, Debug, PartialEq)]
+enum Store {
+    Map(HashMap<String, String>),
+    Compiled(Compiled),
+}
+
+impl Default for Store {
+    fn default() -> Self {
+        Store::Map(HashMap::new())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Compiled {
+    count: usize,
+    // Where the data starts in bytes.
+    data: usize,
+    bytes: Vec<u8>,
+}
+
+impl Definitions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, word: &str) -> Option<&str> {
+        match &self.0 {
+            Store::Map(map) => map.get(word).map(|d| d.as_str()),
+            Store::Compiled(c) => c.get(word),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match &self.0 {
+            Store::Map(map) => map.len(),
+            Store::Compiled(c) => c.count,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Read a compiled definitions file, or a JSON file of words and definitions.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let bytes = std::fs::read(path)?;
+        match bytes.starts_with(MAGIC) {
+            true => Ok(Self(Store::Compiled(Compiled::new(bytes)?))),
+            false => Ok(Self(Store::Map(serde_json::from_slice(&bytes)?))),
+        }
+    }
+
+    /// Write the definitions in the compiled format.
+    pub fn save<W: std::io::Write>(&self, w: &mut W) -> std::io::Result<()> {
+        let mut entries: Vec<(&str, &str)> = match &self.0 {
+            Store::Map(map) => map.iter().map(|(w, d)| (w.as_str(), d.as_str())).collect(),
+            Store::Compiled(c) => return w.write_all(&c.bytes),
+        };
+        entries.sort();
+
+        w.write_all(MAGIC)?;
+        w.write_all(&(entries.len() as u32).to_le_bytes())?;
+        let mut offset = 0;
+        w.write_all(&0u32.to_le_bytes())?;
+        for (word, def) in &entries {
+            offset += word.len() + 1 + def.len();
+            w.write_all(&(offset as u32).to_le_bytes())?;
+        }
+        for (word, def) in &entries {
+            w.write_all(word.as_bytes())?;
+            w.write_all(b"\0")?;
+            w.write_all(def.as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+impl From<HashMap<String, String>> for Definitions {
+    fn from(map: HashMap<String, String>) -> Self {
+        Self(Store::Map(map))
+    }
+}
+
+impl<const N: usize> From<[(String, String); N]> for Definitions {
+    fn from(entries: [(String, String); N]) -> Self {
+        Self(Store::Map(HashMap::from(entries)))
+    }
+}
+
+impl Compiled {
+    // Check that the offsets make sense, so that lookups don't have to.
+    fn new(bytes: Vec<u8>) -> Result<Self, Box<dyn Error>> {
+        let invalid = || -> Box<dyn Error> { "invalid compiled definitions file".into() };
+        let count = read_u32(&bytes, MAGIC.len()).ok_or_else(invalid)? as usize;
+        let data = MAGIC.len() + 4 + 4 * (count + 1);
+        if bytes.len() < data {
+            return Err(invalid());
+        }
+        let mut res = Self { count, data, bytes };
+        let mut prev = 0;
+        for i in 0..=count {
+            let offset = res.offset(i);
+            if offset < prev || res.data + offset > res.bytes.len() {
+                return Err(invalid());
+            }
+            prev = offset;
+        }
+        if res.data + prev != res.bytes.len() {
+            return Err(invalid());
+        }
+        res.bytes.shrink_to_fit();
+        Ok(res)
+    }
+
+    fn offset(&self, i: usize) -> usize {
+        read_u32(&self.bytes, MAGIC.len() + 4 + 4 * i).unwrap_or_default() as usize
+    }
+
+    // The word and definition bytes of record i.
+    fn record(&self, i: usize) -> (&[u8], &[u8]) {
+        let record = &self.bytes[self.data + self.offset(i)..self.data + self.offset(i + 1)];
+        match record.iter().position(|b| *b == 0) {
+            Some(n) => (&record[..n], &record[n + 1..]),
+            None => (record, &[]),
+        }
+    }
+
+    fn get(&self, word: &str) -> Option<&str> {
+        let (mut lo, mut hi) = (0, self.count);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            let (w, def) = self.record(mid);
+            match w.cmp(word.as_bytes()) {
+                std::cmp::Ordering::Less => lo = mid + 1,
+                std::cmp::Ordering::Greater => hi = mid,
+                std::cmp::Ordering::Equal => return std::str::from_utf8(def).ok(),
+            }
+        }
+        None
+    }
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> Option<u32> {
+    let b = bytes.get(at..at + 4)?;
+    Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Compiled, Definitions};
+
+    #[test]
+    fn compiled() {
+        let defs = Definitions::from([
+            ("cat".to_owned(), "mrow".to_owned()),
+            ("cats".to_owned(), "mrow mrow".to_owned()),
+            ("façade".to_owned(), "a front".to_owned()),
+            ("dog".to_owned(), "".to_owned()),
+        ]);
+        let mut w = vec![];
+        defs.save(&mut w).unwrap();
+
+        let compiled = Compiled::new(w.clone()).unwrap();
+        assert_eq!(compiled.count, 4);
+        for word in ["cat", "cats", "façade", "dog", "ca", "catsup", "zebra"] {
+            assert_eq!(compiled.get(word), defs.get(word), "{}", word);
+        }
+
+        assert!(Compiled::new(w[..w.len() - 1].to_vec()).is_err());
+        assert!(Compiled::new(w[..20].to_vec()).is_err());
+    }
+}
@@ -0,0 +1,198 @@
+use clap::ArgEnum;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use super::dictionary::Dictionary;
+use super::wordsearch::{self, Scoring};
+
+// Dice lists from the published Boggle sets. Each die is its six faces. 'Q' is the "Qu" face.
+// '*' marks a face that the text board format can't show (a blank, or a two-letter face other
+// than "Qu"). When one comes up, a random die from the set is rolled in its place, so the
+// two-letter die still only shows "Qu" one time in six.
+
+const CLASSIC: [&str; 16] = [
+    "AACIOT", "ABILTY", "ABJMOQ", "ACDEMP", "ACELRS", "ADENVZ", "AHMORS", "BIFORX", "DENOSW",
+    "DKNOTU", "EEFHIY", "EGKLUY", "EGINTV", "EHINPS", "ELPSTU", "GILRUW",
+];
+
+const NEW: [&str; 16] = [
+    "AAEEGN", "ABBJOO", "ACHOPS", "AFFKPS", "AOOTTW", "CIMOTU", "DEILRX", "DELRVY", "DISTTY",
+    "EEGHNW", "EEINSU", "EHRTVW", "EIOSST", "ELRTTY", "HIMNUQ", "HLNNRZ",
+];
+
+const BIG: [&str; 25] = [
+    "AAAFRS", "AAEEEE", "AAFIRS", "ADENNN", "AEEEEM", "AEEGMU", "AEGMNN", "AFIRSY", "BJKQXZ",
+    "CCENST", "CEIILT", "CEILPT", "CEIPST", "DDHNOT", "DHHLOR", "DHLNOR", "DHLNOR", "EIIITT",
+    "EMOTTT", "ENSSSU", "FIPRSY", "GORRVW", "IPRRRY", "NOOTUW", "OOOTTU",
+];
+
+const SUPER_BIG: [&str; 36] = [
+    "AAAFRS", "AAEEEE", "AAEEOO", "AAFIRS", "ABDEIO", "ADENNN", "AEEEEM", "AEEGMU", "AEGMNN",
+    "AEILMN", "AEINOU", "AFIRSY", "****Q*", // An, Er, He, In, Qu, Th
+    "BBJKXZ", "CCENST", "CDDLNN", "CEIITT", "CEIPST", "CFGNUY", "DDHNOT", "DHHLOR", "DHHNOW",
+    "DHLNOR", "EHILRS", "EIILST", "EILPST", "EIO***", "EMTTTO", "ENSSSU", "GORRVW", "HIRSTV",
+    "HOPRST", "IPRSYY", "JKQWXZ", "NOOTUW", "OOOTTU",
+];
+
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiceSet {
+    /// 4x4 Boggle, before 1987.
+    Classic,
+    /// 4x4 Boggle, 1987 and later.
+    New,
+    /// 5x5 Big Boggle.
+    Big,
+    /// 6x6 Super Big Boggle.
+    SuperBig,
+}
+
+impl DiceSet {
+    pub fn dice(&self) -> &'static [&'static str] {
+        match self {
+            DiceSet::Classic => &CLASSIC,
+            DiceSet::New => &NEW,
+            DiceSet::Big => &BIG,
+            DiceSet::SuperBig => &SUPER_BIG,
+        }
+    }
+
+    /// The number of rows (and columns) on the board.
+    pub fn size(&self) -> usize {
+        match self {
+            DiceSet::Classic | DiceSet::New => 4,
+            DiceSet::Big => 5,
+            DiceSet::SuperBig => 6,
+        }
+    }
+}
+
+/// Shake the dice into the grid and roll each one. The result is one line per row, in the same
+/// format as the files in testdata.
+pub fn roll<R: Rng>(set: DiceSet, rng: &mut R) -> String {
+    let mut dice = set.dice().to_vec();
+    dice.shuffle(rng);
+    let mut res = String::new();
+    for row in dice.chunks(set.size()) {
+        for die in row {
+            res.push(roll_die(set.dice(), die, rng));
+        }
+        res.push('\n');
+    }
+    res
+}
+
+fn roll_die<R: Rng>(dice: &[&str], die: &str, rng: &mut R) -> char {
+    let mut die = die;
+    loop {
+        let faces: Vec<char> = die.chars().collect();
+        match faces.choose(rng) {
+            Some('*') | None => die = dice.choose(rng).unwrap(),
+            Some(face) => return *face,
+        }
+    }
+}
+
+/// Roll boards until one has at least `min_words` words and `min_score` points with `scoring`.
+/// Returns None if none of `max_tries` boards is good enough.
+pub fn generate<R: Rng>(
+    set: DiceSet,
+    rng: &mut R,
+    dict: &Dictionary,
+    scoring: &Scoring,
+    min_words: usize,
+    min_score: u32,
+    max_tries: usize,
+) -> Option<String> {
+    for _ in 0..max_tries {
+        let raw_board = roll(set, rng);
+        if min_words == 0 && min_score == 0 {
+            return Some(raw_board);
+        }
+        // Skip boards with a letter the dictionary's alphabet doesn't have.
+        let board = match wordsearch::boggled_in(&raw_board, &dict.alphabet) {
+            Ok(board) => board,
+            Err(_) => continue,
+        };
+        let words: Vec<String> = wordsearch::find_words(dict, &board)
+            .into_iter()
+            .filter(|w| scoring.counts(w))
+            .collect();
+        let score: u32 = words.iter().map(|w| scoring.score(w)).sum();
+        if words.len() >= min_words && score >= min_score {
+            return Some(raw_board);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::{generate, roll, roll_die, DiceSet};
+    use crate::dictionary::build_dictionary;
+    use crate::wordsearch::{boggled, Scoring};
+
+    const ALL: [DiceSet; 4] = [
+        DiceSet::Classic,
+        DiceSet::New,
+        DiceSet::Big,
+        DiceSet::SuperBig,
+    ];
+
+    #[test]
+    fn dice_fill_the_board() {
+        for set in ALL {
+            assert_eq!(set.dice().len(), set.size() * set.size(), "{:?}", set);
+            for die in set.dice() {
+                assert_eq!(die.len(), 6, "{:?} {}", set, die);
+            }
+        }
+    }
+
+    #[test]
+    fn rolls_are_boggled() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for set in ALL {
+            let board = boggled(&roll(set, &mut rng)).unwrap();
+            assert_eq!(board.size(), set.size());
+        }
+    }
+
+    #[test]
+    fn blank_faces_roll_another_die() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let dice = DiceSet::SuperBig.dice();
+        let qs = (0..6000)
+            .filter(|_| roll_die(dice, "****Q*", &mut rng) == 'Q')
+            .count();
+        // About 1000, plus the odd Q from the die that was rolled instead.
+        assert!((800..1300).contains(&qs), "{}", qs);
+    }
+
+    #[test]
+    fn same_seed_same_board() {
+        let a = roll(DiceSet::Big, &mut StdRng::seed_from_u64(42));
+        let b = roll(DiceSet::Big, &mut StdRng::seed_from_u64(42));
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn generate_gives_up() {
+        let dict = build_dictionary(vec!["cat"]);
+        let mut rng = StdRng::seed_from_u64(1);
+        assert_eq!(
+            generate(
+                DiceSet::Classic,
+                &mut rng,
+                &dict,
+                &Scoring::default(),
+                1000,
+                0,
+                10
+            ),
+            None
+        );
+    }
+}
@@ -3,30 +3,32 @@ use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::fs::{read_to_string, File};
 use std::io::{BufRead, BufReader};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+use super::alphabet::Alphabet;
+use super::config::ConfigError;
+use super::locate::{trace, SearchPath};
+use super::overlay::Overlay;
+
 // DAWG based on https://jbp.dev/blog/dawg-basics.html
 // and https://github.com/sile/rust-dawg
 
 const JSON_DICT: &str = "DICT.json";
 const DICT: &str = "cached.dict";
-const DIR: &str = "/Users/spraints/src/github.com/spraints/boggle-ocr";
-
-const DEBUG: bool = false;
+const DEFS: &str = "cached.defs";
 
-pub type Definitions = HashMap<String, String>;
+pub use super::definitions::Definitions;
 
-// In the Dictionary, each letter is represented by its offset from 'a'.
-#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+// In the Dictionary, each letter is represented by its position in the dictionary's alphabet.
+// try_letter_pos and ch use the English alphabet, where that's the offset from 'a'.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Letter(usize);
 
-pub const Q: Letter = Letter(16);
-pub const U: Letter = Letter(20);
-
 impl Letter {
+    #[cfg(test)]
     pub fn new(ch: char) -> Self {
-        letter_pos(ch)
+        try_letter_pos(ch).unwrap()
     }
 
     pub fn for_i(i: usize) -> Self {
@@ -50,6 +52,7 @@ impl Letter {
     }
 }
 
+#[cfg(test)]
 pub fn l(ch: char) -> Letter {
     Letter::new(ch)
 }
@@ -65,111 +68,245 @@ impl std::fmt::Debug for Letter {
 }
 
 pub fn open_defs(path: &Option<String>) -> Result<Definitions, Box<dyn Error>> {
-    let path = match path {
-        Some(ref p) => p,
-        None => JSON_DICT,
-    };
-    open_defs_path(path)
+    match path {
+        Some(p) => open_defs_path(p),
+        None => open_defs_path(default_defs_path()),
+    }
 }
 
+/// The definitions from the config files, the ones that compile wrote next to a dictionary picked
+/// by $BOGGLE_OCR_DICT or the config files, or the compiled definitions if they're on the search
+/// path, otherwise the JSON dictionary.
+pub fn default_defs_path() -> String {
+    let search = SearchPath::from_env();
+    if let Some((path, _)) = search.configured("dictionary.defs") {
+        return path.display().to_string();
+    }
+    // compile writes the definitions next to a picked dictionary.
+    if let Some((dict, _)) = search.configured("dictionary.dict") {
+        let path = dict.with_extension("defs");
+        if path.is_file() {
+            trace(format!(
+                "found {} next to {}",
+                path.display(),
+                dict.display()
+            ));
+            return path.display().to_string();
+        }
+    }
+    for name in [DEFS, JSON_DICT] {
+        if let Ok((path, _)) = search.find(name) {
+            return path.display().to_string();
+        }
+    }
+    JSON_DICT.to_owned()
+}
+
+/// The compiled dictionary to use without a --dict: $BOGGLE_OCR_DICT, the configured one, or
+/// cached.dict on the search path.
+pub fn default_dict_path() -> Result<String, DError> {
+    let search = SearchPath::from_env();
+    if let Some((path, _)) = search.configured("dictionary.dict") {
+        return Ok(path.display().to_string());
+    }
+    match search.find(DICT) {
+        Ok((path, _)) => Ok(path.display().to_string()),
+        Err(tried) => Err(DError::NotFound { tried }),
+    }
+}
+
+/// Read compiled definitions or a JSON dictionary.
 pub fn open_defs_path(path: impl AsRef<Path>) -> Result<Definitions, Box<dyn Error>> {
-    let f = File::open(path)?;
-    let defs = serde_json::from_reader(f)?;
-    Ok(defs)
+    Definitions::open(path)
+}
+
+pub fn open_json(path: &str, alphabet: Arc<Alphabet>) -> Result<(Dictionary, Definitions), DError> {
+    let j = read_to_string(path).map_err(|err| DError::open(path, err))?;
+    parse_dict_in(&j, alphabet)
 }
 
-pub fn open_json(path: &str) -> Result<(Dictionary, Definitions), Box<dyn Error>> {
-    let j = magic_read_to_string(path)?;
-    parse_dict(&j)
+pub fn parse_dict(j: &str) -> Result<(Dictionary, Definitions), DError> {
+    parse_dict_in(j, Alphabet::english())
 }
 
-pub fn parse_dict(j: &str) -> Result<(Dictionary, Definitions), Box<dyn Error>> {
+/// Parse a JSON dictionary. Words with characters that aren't in the alphabet are skipped.
+pub fn parse_dict_in(
+    j: &str,
+    alphabet: Arc<Alphabet>,
+) -> Result<(Dictionary, Definitions), DError> {
     let mut de = serde_json::Deserializer::from_str(j);
-    let mut builder = DictionaryBuilder::new();
+    let map = de
+        .deserialize_map(OWLVisitor::new())
+        .map_err(DError::Json)?;
+    let dict = build(map.iter().map(|(w, _)| w.as_str()), alphabet.clone());
+    // Definitions are looked up by the words the solver finds, which are spelled with the
+    // alphabet's letters, so in French "café" is defined as "cafe".
     let mut defs = HashMap::new();
-    let map = de.deserialize_map(OWLVisitor::new())?;
-    for (word, definitions) in map {
-        builder.insert(&word, false);
-        defs.insert(word, definitions);
+    for (w, def) in map {
+        if let Some(letters) = alphabet.letters(&w).filter(|l| !l.is_empty()) {
+            let word: String = letters.iter().map(|l| alphabet.text(*l)).collect();
+            defs.entry(word).or_insert(def);
+        }
     }
-    Ok((builder.into_dict(false), defs))
+    Ok((dict, Definitions::from(defs)))
 }
 
-pub fn open_magic(path: &Option<String>) -> Result<Dictionary, Box<dyn Error>> {
-    let compile_path = match path {
-        Some(ref p) => p,
-        None => DICT,
-    };
-    if let Ok(dict) = read(compile_path) {
-        return Ok(dict);
+/// Read a compiled dictionary, or a JSON dictionary if it isn't compiled. Without a path, this
+/// reads $BOGGLE_OCR_DICT, or cached.dict or DICT.json from the search path: the current
+/// directory, $BOGGLE_OCR_PATH, and the XDG data directories. It doesn't read config files.
+///
+/// ```no_run
+/// let dict = boggle_ocr::open_magic(&Some("owl2.dict".to_owned()))?;
+/// println!("{} words", dict.word_count());
+/// # Ok::<(), boggle_ocr::DError>(())
+/// ```
+pub fn open_magic(path: &Option<String>) -> Result<Dictionary, DError> {
+    if let Some(path) = path {
+        return open_magic_path(path);
     }
+    let search = SearchPath::from_env();
+    if let Some((path, _)) = search.configured("dictionary.dict") {
+        return open_magic_path(path);
+    }
+    let mut tried = vec![];
+    for name in [DICT, JSON_DICT] {
+        match search.find(name) {
+            Ok((path, _)) => return open_magic_path(path),
+            Err(paths) => tried.extend(paths),
+        }
+    }
+    Err(DError::NotFound { tried })
+}
 
-    let json_path = match path {
-        Some(ref p) => p,
-        None => JSON_DICT,
+fn open_magic_path(path: impl AsRef<Path>) -> Result<Dictionary, DError> {
+    let path = path.as_ref();
+    let compiled_err = match read(path) {
+        Ok(dict) => return Ok(dict),
+        Err(err @ DError::NotFound { .. }) => return Err(err),
+        Err(err) => err,
     };
 
-    let j = magic_read_to_string(json_path)?;
+    let j = read_to_string(path).map_err(|err| DError::open(path, err))?;
     let mut de = serde_json::Deserializer::from_str(&j);
-    let mut builder = DictionaryBuilder::new();
-    let map = de.deserialize_map(OWLVisitor::new())?;
-    for (word, _) in map {
-        builder.insert(&word, false);
-    }
-
-    Ok(builder.into_dict(DEBUG))
+    let map = match de.deserialize_map(OWLVisitor::new()) {
+        Ok(map) => map,
+        // A file that got past its first node was meant to be a compiled dictionary.
+        Err(_) if matches!(compiled_err, DError::Corrupt { offset, .. } if offset > 0) => {
+            return Err(compiled_err)
+        }
+        Err(err) => return Err(DError::Json(err)),
+    };
+    let dict = build(map.iter().map(|(w, _)| w.as_str()), Alphabet::english());
+    trace(format!(
+        "loaded {} (JSON dictionary, {} words)",
+        path.display(),
+        dict.word_count()
+    ));
+    Ok(dict)
 }
 
-pub fn read(path: impl AsRef<Path>) -> Result<Dictionary, Box<dyn Error>> {
-    let f = match File::open(&path) {
-        Ok(f) => f,
-        Err(orig_err) => File::open(other_path(path)).or(Err(orig_err))?,
-    };
+/// Read a compiled dictionary.
+///
+/// ```no_run
+/// let dict = boggle_ocr::read("cached.dict")?;
+/// assert!(dict.contains("boggle"));
+/// # Ok::<(), boggle_ocr::DError>(())
+/// ```
+pub fn read(path: impl AsRef<Path>) -> Result<Dictionary, DError> {
+    let path = path.as_ref();
+    let f = File::open(path).map_err(|err| DError::open(path, err))?;
     let mut f = BufReader::new(f);
-    Dictionary::from(&mut f)
+    let dict = Dictionary::from(&mut f)?;
+    trace(format!(
+        "loaded {} (compiled dictionary, {} words)",
+        path.display(),
+        dict.word_count()
+    ));
+    Ok(dict)
 }
 
 const REPORT_TIME: bool = true;
 
 pub fn report_time(label: &str, t: std::time::Instant) {
     if REPORT_TIME {
-        println!("{}: {:.2?}", label, t.elapsed());
+        eprintln!("{}: {:.2?}", label, t.elapsed());
     }
 }
 
-pub fn build_dictionary(mut words: Vec<&str>) -> Dictionary {
+/// Build an English dictionary.
+///
+/// ```
+/// let dict = boggle_ocr::build_dictionary(vec!["cat", "cats"]);
+/// assert!(dict.contains("Cat"));
+/// assert!(!dict.contains("ca"));
+/// ```
+pub fn build_dictionary(words: Vec<&str>) -> Dictionary {
+    build(words, Alphabet::english())
+}
+
+/// Build a dictionary from words in any order. Words with characters that aren't in the alphabet
+/// are skipped.
+///
+/// ```
+/// use std::sync::Arc;
+/// use boggle_ocr::{build, Alphabet};
+///
+/// let french = Arc::new(Alphabet::named("french").unwrap());
+/// let dict = build(["café", "thé", "x1"], french);
+/// assert!(dict.contains("cafe"));
+/// assert_eq!(dict.word_count(), 2);
+/// ```
+pub fn build<'a>(words: impl IntoIterator<Item = &'a str>, alphabet: Arc<Alphabet>) -> Dictionary {
+    let mut words: Vec<Vec<Letter>> = words
+        .into_iter()
+        .filter_map(|w| alphabet.letters(w))
+        .filter(|w| !w.is_empty())
+        .collect();
+    // The builder needs the words in the alphabet's order, which isn't always the order of the
+    // characters.
     words.sort();
-    let mut db = DictionaryBuilder::new();
-    for word in words {
-        db.insert(word, false);
+    words.dedup();
+    let mut db = DictionaryBuilder::new(alphabet);
+    for word in &words {
+        db.insert_letters(word, false);
     }
     db.into_dict(false)
 }
 
 struct DictionaryBuilder {
-    previous_word: Option<String>,
+    alphabet: Arc<Alphabet>,
+    previous_word: Option<Vec<Letter>>,
     nodes: Vec<NodeBuilder>,
-    unchecked: Vec<(usize, char, usize)>,
+    unchecked: Vec<(usize, Letter, usize)>,
     minimized: HashMap<NodeBuilder, usize>,
     words: usize,
 }
 
 impl DictionaryBuilder {
-    fn new() -> Self {
+    fn new(alphabet: Arc<Alphabet>) -> Self {
         Self {
+            nodes: vec![NodeBuilder::new(alphabet.len())],
+            alphabet,
             previous_word: None,
-            nodes: vec![NodeBuilder::new()],
             unchecked: vec![],
             minimized: HashMap::new(),
             words: 0,
         }
     }
 
+    // Words have to be inserted in order.
+    #[cfg(test)]
     fn insert(&mut self, word: &str, debug: bool) {
+        if let Some(letters) = self.alphabet.letters(word) {
+            self.insert_letters(&letters, debug);
+        }
+    }
+
+    fn insert_letters(&mut self, word: &[Letter], debug: bool) {
         self.words += 1;
 
         if debug {
-            println!("inserting '{}'", word);
+            println!("inserting '{}'", self.spell(word));
         }
 
         let common_prefix = self.common_prefix(word);
@@ -183,12 +320,12 @@ impl DictionaryBuilder {
             Some((_, _, x)) => *x,
         };
 
-        for letter in word.chars().skip(common_prefix) {
+        for letter in word.iter().copied().skip(common_prefix) {
             if debug {
-                println!("  adding a node for '{}'", letter);
+                println!("  adding a node for '{}'", self.alphabet.text(letter));
             }
             let next_node_idx = self.nodes.len();
-            self.nodes.push(NodeBuilder::new());
+            self.nodes.push(NodeBuilder::new(self.alphabet.len()));
             self.nodes[node_idx].set_child(letter, next_node_idx);
             self.unchecked.push((node_idx, letter, next_node_idx));
             node_idx = next_node_idx;
@@ -196,7 +333,11 @@ impl DictionaryBuilder {
 
         self.nodes[node_idx].terminal = true;
 
-        self.previous_word = Some(String::from(word));
+        self.previous_word = Some(word.to_vec());
+    }
+
+    fn spell(&self, word: &[Letter]) -> String {
+        word.iter().map(|l| self.alphabet.text(*l)).collect()
     }
 
     fn into_dict(mut self, debug: bool) -> Dictionary {
@@ -213,11 +354,15 @@ impl DictionaryBuilder {
                 sz1
             );
         }
-        Dictionary { root }
+        Dictionary {
+            root,
+            overlay: None,
+            alphabet: self.alphabet,
+        }
     }
 
     fn map(&self, idx: usize, nodes: &mut HashMap<usize, Arc<Node>>) -> Node {
-        let mut node = Node::new();
+        let mut node = Node::new(self.alphabet.len());
         let nb = &self.nodes[idx];
         node.terminal = nb.terminal;
         node.id = idx;
@@ -232,14 +377,15 @@ impl DictionaryBuilder {
                 }
             }
         }
+        node.count_words();
         node
     }
 
-    fn common_prefix(&self, word: &str) -> usize {
+    fn common_prefix(&self, word: &[Letter]) -> usize {
         match &self.previous_word {
             None => 0,
             Some(w) => {
-                let z = word.chars().zip(w.chars());
+                let z = word.iter().zip(w.iter());
                 let mut max_i = 0;
                 for (i, (a, b)) in z.enumerate() {
                     if a != b {
@@ -260,7 +406,7 @@ impl DictionaryBuilder {
                     if debug {
                         println!(
                             "  - minimizing '{}': {} {} => {} {}",
-                            letter,
+                            self.alphabet.text(letter),
                             child_idx,
                             self.describe(child_idx),
                             *new_child_idx,
@@ -273,7 +419,7 @@ impl DictionaryBuilder {
                     if debug {
                         println!(
                             "  - '{}' ({} {}) is already minimized",
-                            letter,
+                            self.alphabet.text(letter),
                             child_idx,
                             self.describe(child_idx)
                         );
@@ -291,7 +437,7 @@ impl DictionaryBuilder {
         for (i, oidx) in node.children.iter().enumerate() {
             let pos = Letter(i);
             if let Some(idx) = oidx {
-                res.push(letter_for_pos(pos));
+                res.push_str(self.alphabet.text(pos));
                 if node.terminal {
                     res.push('!');
                 }
@@ -312,58 +458,238 @@ struct NodeBuilder {
 }
 
 impl NodeBuilder {
-    fn new() -> Self {
+    fn new(size: usize) -> Self {
         Self {
             terminal: false,
-            children: vec![None; 26],
+            children: vec![None; size],
         }
     }
 
-    fn set_child(&mut self, letter: char, child_idx: usize) {
-        self.children[letter_pos(letter).0] = Some(child_idx);
+    fn set_child(&mut self, letter: Letter, child_idx: usize) {
+        self.children[letter.0] = Some(child_idx);
     }
 }
 
 #[derive(Clone)]
 pub struct Dictionary {
     pub root: Node,
+    /// House rules to apply on top of the compiled words.
+    pub overlay: Option<Arc<Overlay>>,
+    pub alphabet: Arc<Alphabet>,
 }
 
 impl Dictionary {
+    pub fn with_overlay(self, overlay: Overlay) -> Self {
+        Self {
+            overlay: Some(Arc::new(overlay)),
+            ..self
+        }
+    }
+
+    /// The roots to search for words: the compiled words and the overlay's added words. Searches
+    /// should skip words that aren't `allowed`.
+    pub fn roots(&self) -> impl Iterator<Item = &Node> {
+        std::iter::once(&self.root).chain(self.overlay.iter().map(|o| &o.dict.root))
+    }
+
+    /// Whether the overlay allows a word that is in one of the `roots`.
+    pub fn allowed(&self, word: &str) -> bool {
+        match &self.overlay {
+            Some(o) => !o.removed.contains_key(word),
+            None => true,
+        }
+    }
+
+    /// The overlay's note about a word, which takes the place of its definition.
+    pub fn note(&self, word: &str) -> Option<&str> {
+        let overlay = self.overlay.as_ref()?;
+        let letters = self.alphabet.letters(word)?;
+        overlay.note(&self.spell(&letters))
+    }
+
+    /// Check whether a word is in the dictionary. Case and accents the alphabet folds away don't
+    /// matter.
+    pub fn contains(&self, word: &str) -> bool {
+        let letters = match self.alphabet.letters(word) {
+            Some(letters) => letters,
+            None => return false,
+        };
+        self.allowed(&self.spell(&letters))
+            && self.roots().any(|root| node_contains(root, &letters))
+    }
+
+    /// Spell a word from its letters.
+    pub fn spell(&self, word: &[Letter]) -> String {
+        word.iter().map(|l| self.alphabet.text(*l)).collect()
+    }
+
+    /// Count the words in the dictionary.
+    pub fn word_count(&self) -> usize {
+        let count = self.root.words;
+        match &self.overlay {
+            None => count,
+            Some(o) => {
+                let in_root = |w: &str| match self.alphabet.letters(w) {
+                    Some(letters) => node_contains(&self.root, &letters),
+                    None => false,
+                };
+                let added = o
+                    .added
+                    .keys()
+                    .filter(|w| !in_root(w) && !o.removed.contains_key(*w));
+                let removed = o.removed.keys().filter(|w| in_root(w));
+                count + added.count() - removed.count()
+            }
+        }
+    }
+
+    /// The word's position among the compiled words in alphabetical order, starting at 0. Every
+    /// word has a different rank, and the ranks go from 0 to one less than the number of words.
+    /// Overlays don't change ranks.
+    pub fn rank(&self, word: &str) -> Option<usize> {
+        let mut rank = 0;
+        let mut node = &self.root;
+        for l in self.alphabet.letters(word)? {
+            if node.terminal {
+                rank += 1;
+            }
+            for i in 0..l.i() {
+                if let Some(child) = node.lookup(Letter(i)) {
+                    rank += child.words;
+                }
+            }
+            node = node.lookup(l)?;
+        }
+        match node.terminal {
+            true => Some(rank),
+            false => None,
+        }
+    }
+
+    /// The word with the given rank.
+    pub fn word(&self, mut rank: usize) -> Option<String> {
+        if rank >= self.root.words {
+            return None;
+        }
+        let mut res = String::new();
+        let mut node = &self.root;
+        loop {
+            if node.terminal {
+                if rank == 0 {
+                    return Some(res);
+                }
+                rank -= 1;
+            }
+            let (l, child) = node.children().find(|(_, child)| {
+                if rank < child.words {
+                    return true;
+                }
+                rank -= child.words;
+                false
+            })?;
+            res.push_str(self.alphabet.text(l));
+            node = child;
+        }
+    }
+
+    /// Write the compiled dictionary. Dictionaries that aren't English start with the alphabet,
+    /// e.g. "{german a b c ... z ä ö ü ß};". Files without it are English.
     pub fn save<W: std::io::Write>(&self, w: &mut W) -> std::io::Result<()> {
+        if !self.alphabet.is_english() {
+            write!(w, "{};", self.alphabet.header())?;
+        }
         let mut written = HashSet::new();
         self.root.save(w, &mut written)
     }
 
-    fn from<R: BufRead>(r: &mut R) -> Result<Self, Box<dyn Error>> {
+    fn from<R: BufRead>(r: &mut R) -> Result<Self, DError> {
+        let mut offset = 0;
+        let alphabet = match r.fill_buf()?.first() {
+            Some(b'{') => {
+                let mut header = vec![];
+                offset = r.read_until(b';', &mut header)?;
+                let header = String::from_utf8_lossy(&header);
+                match Alphabet::from_header(header.trim_end_matches(';')) {
+                    Some(alphabet) => Arc::new(alphabet),
+                    None => {
+                        return Err(DError::Corrupt {
+                            offset: 0,
+                            reason: Corruption::InvalidHeader(header.into_owned()),
+                        })
+                    }
+                }
+            }
+            _ => Alphabet::english(),
+        };
         let mut nodes = HashMap::new();
         let mut root = None;
-        while let Some(node) = Node::from(r, &mut nodes)? {
+        while let Some((node, n)) = Node::from(r, &mut nodes, alphabet.len(), offset)? {
             root = Some(node);
+            offset += n;
         }
         match root {
-            None => Err(Box::new(DError::NoNodesInInput)),
-            Some(node) => Ok(Self { root: node }),
+            None => Err(DError::Corrupt {
+                offset,
+                reason: Corruption::NoNodes,
+            }),
+            Some(node) => Ok(Self {
+                root: node,
+                overlay: None,
+                alphabet,
+            }),
         }
     }
 }
 
+fn node_contains(root: &Node, word: &[Letter]) -> bool {
+    let mut node = root;
+    for l in word {
+        match node.lookup(*l) {
+            Some(n) => node = n,
+            None => return false,
+        };
+    }
+    node.terminal
+}
+
 #[derive(Clone)]
 pub struct Node {
     pub terminal: bool,
     id: usize,
+    // How many words end at or below this node.
+    words: usize,
     children: Vec<Option<Arc<Node>>>,
 }
 
 impl Node {
-    fn new() -> Self {
+    fn new(size: usize) -> Self {
         Self {
             terminal: false,
             id: 0,
-            children: vec![None; 26],
+            words: 0,
+            children: vec![None; size],
         }
     }
 
+    /// How many words end at or below this node.
+    pub fn words(&self) -> usize {
+        self.words
+    }
+
+    // Nodes are built after their children, so this can be called once the children are set.
+    fn count_words(&mut self) {
+        let below: usize = self.children.iter().flatten().map(|c| c.words).sum();
+        self.words = below + usize::from(self.terminal);
+    }
+
+    /// The letters that can follow this node, in alphabet order.
+    pub fn children(&self) -> impl Iterator<Item = (Letter, &Node)> {
+        self.children
+            .iter()
+            .enumerate()
+            .filter_map(|(i, child)| child.as_deref().map(|c| (Letter(i), c)))
+    }
+
     pub fn lookup(&self, ch: Letter) -> Option<&Node> {
         match self.children.get(ch.0) {
             Some(Some(rc_node)) => Some(rc_node),
@@ -388,31 +714,36 @@ impl Node {
         Ok(())
     }
 
+    // Read the node that starts at `offset`. Returns the node and how many bytes it took up.
     fn from<R: BufRead>(
         r: &mut R,
         nodes: &mut HashMap<usize, Arc<Node>>,
-    ) -> Result<Option<Self>, Box<dyn Error>> {
+        size: usize,
+        offset: usize,
+    ) -> Result<Option<(Self, usize)>, DError> {
         let mut data = vec![];
         let n = r.read_until(b';', &mut data)?;
         if n == 0 {
             return Ok(None);
         }
         //println!("READ '{}'", std::str::from_utf8(&data).unwrap());
-        let mut res = Self::new();
-        res.parse(data, nodes)?;
+        let mut res = Self::new(size);
+        res.parse(data, nodes)
+            .map_err(|reason| DError::Corrupt { offset, reason })?;
+        res.count_words();
         nodes.insert(res.id, Arc::new(res.clone()));
-        Ok(Some(res))
+        Ok(Some((res, n)))
     }
 
     fn parse(
         &mut self,
         s: Vec<u8>,
         nodes: &mut HashMap<usize, Arc<Node>>,
-    ) -> Result<(), Box<dyn Error>> {
+    ) -> Result<(), Corruption> {
         let mut c = s.iter();
         match c.next() {
             Some(b'[') => (),
-            _ => return Err(Box::new(DError::InvalidNode(s))),
+            _ => return Err(Corruption::InvalidNode(s)),
         };
 
         loop {
@@ -434,7 +765,7 @@ impl Node {
                 Some(b']') => {
                     break;
                 }
-                _ => return Err(Box::new(DError::InvalidNode(s))),
+                _ => return Err(Corruption::InvalidNode(s)),
             };
         }
 
@@ -443,7 +774,7 @@ impl Node {
                 return Ok(());
             }
             Some(b' ') => {}
-            _ => return Err(Box::new(DError::InvalidNode(s))),
+            _ => return Err(Corruption::InvalidNode(s)),
         };
 
         let mut st = NodeRefParseState::new();
@@ -490,7 +821,7 @@ impl Node {
                     st.commit(self, nodes)?;
                     break;
                 }
-                _ => return Err(Box::new(DError::InvalidNode(s))),
+                _ => return Err(Corruption::InvalidNode(s)),
             }
         }
 
@@ -526,35 +857,25 @@ impl NodeRefParseState {
         }
     }
 
-    fn commit(self, node: &mut Node, nodes: &HashMap<usize, Arc<Node>>) -> Result<(), DError> {
-        match nodes.get(&self.child_id) {
-            Some(child) => {
-                node.children[self.pos] = Some(child.clone());
+    fn commit(self, node: &mut Node, nodes: &HashMap<usize, Arc<Node>>) -> Result<(), Corruption> {
+        match (nodes.get(&self.child_id), node.children.get_mut(self.pos)) {
+            (Some(child), Some(slot)) => {
+                *slot = Some(child.clone());
                 Ok(())
             }
-            None => Err(DError::DanglingPointer(self.child_id)),
+            (None, _) => Err(Corruption::DanglingPointer(self.child_id)),
+            (_, None) => Err(Corruption::LetterOutOfRange(self.pos)),
         }
     }
 }
 
 pub fn try_letter_pos(letter: char) -> Option<Letter> {
-    letter.to_lowercase().next().and_then(|ch| {
-        let pos = ch as u8 - b'a';
-        if pos < 26 {
-            Some(Letter(pos as usize))
-        } else {
-            None
-        }
+    letter.to_lowercase().next().and_then(|ch| match ch {
+        'a'..='z' => Some(Letter(ch as usize - 'a' as usize)),
+        _ => None,
     })
 }
 
-pub fn letter_pos(letter: char) -> Letter {
-    match try_letter_pos(letter) {
-        Some(l) => l,
-        None => unreachable!("can't get letter_pos for {letter}"),
-    }
-}
-
 pub fn letter_for_pos(pos: Letter) -> char {
     let Letter(pos) = pos;
     assert!(pos < 26);
@@ -592,7 +913,7 @@ mod test {
     use super::Letter;
 
     fn make_test_dictionary(debug: bool) -> super::Dictionary {
-        let mut builder = super::DictionaryBuilder::new();
+        let mut builder = super::DictionaryBuilder::new(super::Alphabet::english());
         builder.insert("cat", debug);
         builder.insert("cats", debug);
         builder.insert("fact", debug);
@@ -630,6 +951,40 @@ mod test {
         check_test_words(&dict);
     }
 
+    #[test]
+    fn contains() {
+        let dict = make_test_dictionary(false);
+        assert!(dict.contains("facet"));
+        assert!(dict.contains("Facet"));
+        assert!(!dict.contains("face"));
+        assert!(!dict.contains("facets!"));
+        assert!(!dict.contains(""));
+    }
+
+    #[test]
+    fn word_count() {
+        assert_eq!(make_test_dictionary(false).word_count(), 6);
+    }
+
+    #[test]
+    fn ranks() {
+        let words = ["cat", "cats", "facet", "facets", "fact", "facts"];
+        let dict = make_test_dictionary(false);
+        let mut w = Vec::new();
+        dict.save(&mut w).unwrap();
+        let read = super::Dictionary::from(&mut w.as_slice()).unwrap();
+        for dict in [dict, read] {
+            for (i, word) in words.iter().enumerate() {
+                assert_eq!(dict.rank(word), Some(i), "{}", word);
+                assert_eq!(dict.word(i).as_deref(), Some(*word));
+            }
+            assert_eq!(dict.word(6), None);
+            assert_eq!(dict.rank("ca"), None);
+            assert_eq!(dict.rank("dog"), None);
+            assert_eq!(dict.rank("c4t"), None);
+        }
+    }
+
     #[test]
     fn rw_dict() {
         let dict = make_test_dictionary(false);
@@ -643,6 +998,47 @@ mod test {
         check_test_words(&dict);
     }
 
+    #[test]
+    fn rw_spanish_dict() {
+        let spanish = super::Alphabet::named("spanish").unwrap();
+        let dict = super::build(["año", "ano", "ñu", "zorro"], std::sync::Arc::new(spanish));
+
+        let mut w = Vec::new();
+        dict.save(&mut w).unwrap();
+        assert!(w.starts_with(b"{spanish a b c"));
+
+        let mut r = w.as_slice();
+        let dict = super::Dictionary::from(&mut r).unwrap();
+        assert_eq!(dict.alphabet.name(), "spanish");
+        assert!(dict.contains("Año"));
+        assert!(dict.contains("ano"));
+        assert!(!dict.contains("an"));
+        // ñ comes after n.
+        assert_eq!(dict.word(1), Some("año".to_owned()));
+        assert_eq!(dict.word(2), Some("ñu".to_owned()));
+    }
+
+    #[test]
+    fn corrupt_dict() {
+        use super::{Corruption, DError};
+
+        let mut r = "[1!];[0] 0:1 1:7;".as_bytes();
+        match super::Dictionary::from(&mut r) {
+            Err(DError::Corrupt { offset, reason }) => {
+                assert_eq!(offset, 5);
+                assert_eq!(reason, Corruption::DanglingPointer(7));
+            }
+            res => panic!("expected a corrupt dictionary, got {:?}", res.err()),
+        }
+
+        match super::read("no/such/dictionary") {
+            Err(DError::NotFound { tried }) => {
+                assert_eq!(tried, vec![std::path::PathBuf::from("no/such/dictionary")])
+            }
+            res => panic!("expected no dictionary, got {:?}", res.err()),
+        }
+    }
+
     #[test]
     fn open_dict_js() {
         let (dict, defs) = super::parse_dict(TEST_DICT).unwrap();
@@ -660,6 +1056,19 @@ mod test {
         );
     }
 
+    #[test]
+    fn open_french_dict_js() {
+        let french = std::sync::Arc::new(super::Alphabet::named("french").unwrap());
+        let (dict, defs) =
+            super::parse_dict_in(r#"{"café": "coffee", "Thé": "tea", "x2": "no"}"#, french)
+                .unwrap();
+        assert!(dict.contains("cafe"));
+        assert_eq!(defs.get("cafe"), Some("coffee"));
+        assert_eq!(defs.get("the"), Some("tea"));
+        assert_eq!(defs.get("café"), None);
+        assert_eq!(defs.len(), 2);
+    }
+
     fn make_some_words(n: usize, node: &super::Node) -> Vec<String> {
         let mut res = vec![];
         if node.terminal {
@@ -682,41 +1091,98 @@ mod test {
     }
 }
 
-fn magic_read_to_string<P: AsRef<Path>>(path: P) -> std::io::Result<String> {
-    match read_to_string(&path) {
-        Ok(f) => Ok(f),
-        Err(orig_err) => read_to_string(other_path(path)).or(Err(orig_err)),
-    }
-}
-
-fn other_path<P: AsRef<Path>>(path: P) -> std::path::PathBuf {
-    Path::new(DIR).join(path)
+/// Why a dictionary couldn't be opened.
+#[derive(Debug)]
+pub enum DError {
+    /// The dictionary isn't at any of these paths.
+    NotFound {
+        tried: Vec<PathBuf>,
+    },
+    Io(std::io::Error),
+    /// The compiled dictionary is damaged at this byte offset.
+    Corrupt {
+        offset: usize,
+        reason: Corruption,
+    },
+    /// The JSON dictionary isn't valid.
+    Json(serde_json::Error),
+    /// A config file isn't valid.
+    Config(ConfigError),
 }
 
-// TODO - use thiserror
-#[derive(Debug)]
-enum DError {
-    NoNodesInInput,
+#[derive(Debug, PartialEq)]
+pub enum Corruption {
+    NoNodes,
+    InvalidHeader(String),
     InvalidNode(Vec<u8>),
     DanglingPointer(usize),
+    LetterOutOfRange(usize),
+}
+
+impl DError {
+    // An error from opening `path`.
+    fn open(path: impl AsRef<Path>, err: std::io::Error) -> Self {
+        match err.kind() {
+            std::io::ErrorKind::NotFound => DError::NotFound {
+                tried: vec![path.as_ref().to_owned()],
+            },
+            _ => DError::Io(err),
+        }
+    }
+}
+
+impl From<std::io::Error> for DError {
+    fn from(err: std::io::Error) -> Self {
+        DError::Io(err)
+    }
 }
 
 impl std::fmt::Display for DError {
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
-            DError::NoNodesInInput => write!(fmt, "no nodes in input"),
-            DError::InvalidNode(s) => {
+            DError::NotFound { tried } => {
+                let tried: Vec<String> = tried.iter().map(|p| p.display().to_string()).collect();
+                write!(fmt, "no dictionary found, tried {}", tried.join(", "))
+            }
+            DError::Io(err) => write!(fmt, "{}", err),
+            DError::Corrupt { offset, reason } => {
+                write!(fmt, "corrupt dictionary at byte {}: {}", offset, reason)
+            }
+            DError::Json(err) => write!(fmt, "invalid JSON dictionary: {}", err),
+            DError::Config(err) => write!(fmt, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for DError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DError::Io(err) => Some(err),
+            DError::Json(err) => Some(err),
+            DError::Config(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Corruption {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Corruption::NoNodes => write!(fmt, "no nodes in input"),
+            Corruption::InvalidHeader(s) => write!(fmt, "invalid alphabet '{}'", s),
+            Corruption::InvalidNode(s) => {
                 write!(
                     fmt,
                     "invalid node '{}'",
                     std::str::from_utf8(s).unwrap_or("(unprintable)"),
                 )
             }
-            DError::DanglingPointer(child_id) => {
+            Corruption::DanglingPointer(child_id) => {
                 write!(fmt, "node has dangling pointer to {}", child_id)
             }
+            Corruption::LetterOutOfRange(pos) => {
+                write!(fmt, "letter {} isn't in the alphabet", pos)
+            }
         }
     }
 }
-
-impl std::error::Error for DError {}
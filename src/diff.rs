@@ -0,0 +1,92 @@
+use std::collections::BTreeMap;
+
+use super::dictionary::{Dictionary, Letter, Node};
+
+// Compare two dictionaries by walking both DAWGs at the same time, so that neither word list has
+// to be expanded into memory.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Side {
+    /// The word is only in the first dictionary.
+    A,
+    /// The word is only in the second dictionary.
+    B,
+}
+
+/// For each word length, how many words are only in A and how many are only in B.
+pub type LengthCounts = BTreeMap<usize, (usize, usize)>;
+
+/// Call `f` for every word that is in one dictionary but not the other, in alphabetical order.
+/// Returns the counts by word length.
+pub fn diff(a: &Dictionary, b: &Dictionary, mut f: impl FnMut(Side, &str)) -> LengthCounts {
+    let mut counts = LengthCounts::new();
+    walk(
+        a,
+        Some(&a.root),
+        Some(&b.root),
+        &mut String::new(),
+        &mut |side, word| {
+            let entry = counts.entry(word.chars().count()).or_insert((0, 0));
+            match side {
+                Side::A => entry.0 += 1,
+                Side::B => entry.1 += 1,
+            };
+            f(side, word);
+        },
+    );
+    counts
+}
+
+// The dictionaries have the same alphabet, so the letters of `dict` spell words from either one.
+fn walk(
+    dict: &Dictionary,
+    a: Option<&Node>,
+    b: Option<&Node>,
+    scratch: &mut String,
+    f: &mut impl FnMut(Side, &str),
+) {
+    match (a.map(|n| n.terminal), b.map(|n| n.terminal)) {
+        (Some(true), None | Some(false)) => f(Side::A, scratch),
+        (None | Some(false), Some(true)) => f(Side::B, scratch),
+        _ => (),
+    };
+    for i in 0..dict.alphabet.len() {
+        let l = Letter::for_i(i);
+        let a = a.and_then(|n| n.lookup(l));
+        let b = b.and_then(|n| n.lookup(l));
+        if a.is_none() && b.is_none() {
+            continue;
+        }
+        let len = scratch.len();
+        scratch.push_str(dict.alphabet.text(l));
+        walk(dict, a, b, scratch, f);
+        scratch.truncate(len);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{diff, Side};
+    use crate::dictionary::build_dictionary;
+
+    #[test]
+    fn diff_dicts() {
+        let a = build_dictionary(vec!["cat", "cats", "dog", "zebra"]);
+        let b = build_dictionary(vec!["cat", "ca", "dogs", "zebra", "zzz"]);
+        let mut words = vec![];
+        let counts = diff(&a, &b, |side, word| words.push((side, word.to_owned())));
+        assert_eq!(
+            words,
+            vec![
+                (Side::B, "ca".to_owned()),
+                (Side::A, "cats".to_owned()),
+                (Side::A, "dog".to_owned()),
+                (Side::B, "dogs".to_owned()),
+                (Side::B, "zzz".to_owned()),
+            ]
+        );
+        assert_eq!(counts.get(&2), Some(&(0, 1)));
+        assert_eq!(counts.get(&3), Some(&(1, 1)));
+        assert_eq!(counts.get(&4), Some(&(1, 1)));
+    }
+}
@@ -0,0 +1,178 @@
+use std::error::Error;
+use std::path::Path;
+
+use clap::ArgEnum;
+
+use super::dictionary::Dictionary;
+use super::locate::{self, Optional};
+
+// How common each word is, from a word frequency list. The compiled file has one byte per word in
+// the dictionary, in the order of the words' ranks, so it only works with the dictionary it was
+// compiled with.
+
+const MAGIC: &[u8] = b"boggle-ocr freq 1\n";
+const FREQ: &str = "cached.freq";
+
+/// How well known a word is, most common first. Words that aren't in the frequency list are
+/// obscure.
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Tier {
+    Common,
+    Familiar,
+    Uncommon,
+    Rare,
+    Obscure,
+}
+
+impl Tier {
+    // The tier for a word at this position in the frequency list.
+    fn for_position(i: usize) -> Self {
+        match i {
+            0..=2_999 => Tier::Common,
+            3_000..=9_999 => Tier::Familiar,
+            10_000..=29_999 => Tier::Uncommon,
+            _ => Tier::Rare,
+        }
+    }
+
+    fn from_u8(b: u8) -> Self {
+        match b {
+            0 => Tier::Common,
+            1 => Tier::Familiar,
+            2 => Tier::Uncommon,
+            3 => Tier::Rare,
+            _ => Tier::Obscure,
+        }
+    }
+}
+
+pub struct Commonness(Vec<u8>);
+
+impl Commonness {
+    /// Rate every word in the dictionary using a frequency list. Each line of the list is a word,
+    /// optionally followed by how many times it was seen. Without counts, the most common word
+    /// comes first.
+    pub fn build(dict: &Dictionary, list: &str) -> Self {
+        let mut entries: Vec<(&str, u64)> = list
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let word = fields.next()?;
+                let count = fields.next().and_then(|c| c.parse().ok()).unwrap_or(0);
+                Some((word, count))
+            })
+            .collect();
+        // A stable sort keeps the list's order when there are no counts.
+        entries.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+        let mut tiers = vec![Tier::Obscure as u8; dict.root.words()];
+        let mut position = 0;
+        for (word, _) in entries {
+            if let Some(rank) = dict.rank(word) {
+                if tiers[rank] == Tier::Obscure as u8 {
+                    tiers[rank] = Tier::for_position(position) as u8;
+                    position += 1;
+                }
+            }
+        }
+        Self(tiers)
+    }
+
+    pub fn tier(&self, dict: &Dictionary, word: &str) -> Tier {
+        match dict.rank(word).and_then(|rank| self.0.get(rank)) {
+            Some(b) => Tier::from_u8(*b),
+            None => Tier::Obscure,
+        }
+    }
+
+    pub fn save<W: std::io::Write>(&self, w: &mut W) -> std::io::Result<()> {
+        w.write_all(MAGIC)?;
+        w.write_all(&self.0)
+    }
+
+    pub fn open(path: impl AsRef<Path>, dict: &Dictionary) -> Result<Self, Box<dyn Error>> {
+        let bytes = std::fs::read(path)?;
+        match bytes.strip_prefix(MAGIC) {
+            Some(tiers) if tiers.len() == dict.root.words() => Ok(Self(tiers.to_vec())),
+            Some(_) => Err("the frequency file was compiled with a different dictionary".into()),
+            None => Err("invalid frequency file".into()),
+        }
+    }
+}
+
+/// The --freq and --common options: how common words are, if there's a frequency file, and which
+/// words to keep.
+pub struct Filter {
+    freq: Option<Commonness>,
+    max: Option<Tier>,
+}
+
+impl Filter {
+    /// Open the given frequency file, the configured one, or cached.freq if it's on the search path.
+    /// A cached.freq that doesn't go with the dictionary is skipped.
+    pub fn open(
+        path: &Option<String>,
+        max: Option<Tier>,
+        dict: &Dictionary,
+    ) -> Result<Self, Box<dyn Error>> {
+        let freq = match path {
+            Some(path) => Some(Commonness::open(path, dict)?),
+            None => match locate::find_optional("dictionary.freq", FREQ) {
+                Some(Optional::Configured(path)) => Some(Commonness::open(path, dict)?),
+                Some(Optional::Found(path)) => match Commonness::open(&path, dict) {
+                    Ok(freq) => Some(freq),
+                    Err(err) => {
+                        locate::trace(format!("skipping {}: {}", path.display(), err));
+                        None
+                    }
+                },
+                None => None,
+            },
+        };
+        if max.is_some() && freq.is_none() {
+            return Err("--common needs a frequency file, see \"compile --frequencies\"".into());
+        }
+        Ok(Self { freq, max })
+    }
+
+    /// How common a word is, if there's a frequency file.
+    pub fn tier(&self, dict: &Dictionary, word: &str) -> Option<Tier> {
+        self.freq.as_ref().map(|f| f.tier(dict, word))
+    }
+
+    /// Whether a word is at least as common as --common.
+    pub fn keep(&self, dict: &Dictionary, word: &str) -> bool {
+        match (self.tier(dict, word), self.max) {
+            (Some(tier), Some(max)) => tier <= max,
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Commonness, Tier};
+    use crate::dictionary::build_dictionary;
+
+    #[test]
+    fn tiers() {
+        let dict = build_dictionary(vec!["cat", "cats", "dog", "zax"]);
+        let freq = Commonness::build(&dict, "the 500\ndog 20\ncat 300\nCat 1\n");
+        assert_eq!(freq.tier(&dict, "cat"), Tier::Common);
+        assert_eq!(freq.tier(&dict, "dog"), Tier::Common);
+        assert_eq!(freq.tier(&dict, "zax"), Tier::Obscure);
+        assert_eq!(freq.tier(&dict, "the"), Tier::Obscure);
+
+        let mut w = vec![];
+        freq.save(&mut w).unwrap();
+        assert_eq!(&w[super::MAGIC.len()..], &[0, 4, 0, 4]);
+    }
+
+    #[test]
+    fn positions() {
+        assert_eq!(Tier::for_position(0), Tier::Common);
+        assert_eq!(Tier::for_position(3_000), Tier::Familiar);
+        assert_eq!(Tier::for_position(29_999), Tier::Uncommon);
+        assert_eq!(Tier::for_position(100_000), Tier::Rare);
+    }
+}
@@ -0,0 +1,204 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use super::dictionary::Dictionary;
+use super::wordsearch::{self, Scoring};
+
+// The game log is a JSON Lines file with one Game per line. Games are numbered by their position
+// in the file, starting at 1.
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Game {
+    pub date: DateTime<Local>,
+    /// The board, one line per row.
+    pub board: String,
+    /// How many words are on the board.
+    pub total_words: usize,
+    /// How many points are on the board.
+    pub total_score: u32,
+    pub players: Vec<Player>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Player {
+    pub name: String,
+    pub words: Vec<String>,
+    pub score: u32,
+}
+
+impl Game {
+    /// Score a game. Each player's words are checked against the board, and, as in Boggle, words
+    /// that more than one player found don't count.
+    pub fn new(
+        date: DateTime<Local>,
+        board: &str,
+        players: Vec<(String, Vec<String>)>,
+        dict: &Dictionary,
+        scoring: &Scoring,
+    ) -> Result<Self, wordsearch::WSError> {
+        let words: Vec<String> =
+            wordsearch::find_words(dict, &wordsearch::boggled_in(board, &dict.alphabet)?)
+                .into_iter()
+                .filter(|w| scoring.counts(w))
+                .collect();
+        let total_score = words.iter().map(|w| scoring.score(w)).sum();
+        let on_board: HashSet<&str> = words.iter().map(|w| w.as_str()).collect();
+
+        let mut found_by = HashMap::new();
+        for (_, words) in &players {
+            let unique: HashSet<&String> = words.iter().collect();
+            for w in unique {
+                *found_by.entry(w.clone()).or_insert(0) += 1;
+            }
+        }
+
+        let players = players
+            .into_iter()
+            .map(|(name, words)| {
+                let score = words
+                    .iter()
+                    .collect::<HashSet<_>>()
+                    .into_iter()
+                    .filter(|w| on_board.contains(w.as_str()) && found_by[*w] == 1)
+                    .map(|w| scoring.score(w))
+                    .sum();
+                Player { name, words, score }
+            })
+            .collect();
+
+        Ok(Self {
+            date,
+            board: normalize_board(board),
+            total_words: words.len(),
+            total_score,
+            players,
+        })
+    }
+}
+
+fn normalize_board(board: &str) -> String {
+    board
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_uppercase() + "\n")
+        .collect()
+}
+
+/// Read every game in the log. A missing log has no games.
+pub fn load(path: impl AsRef<Path>) -> Result<Vec<Game>, Box<dyn Error>> {
+    let f = match File::open(path) {
+        Ok(f) => f,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(err) => return Err(Box::new(err)),
+    };
+    let mut res = vec![];
+    for line in BufReader::new(f).lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            res.push(serde_json::from_str(&line)?);
+        }
+    }
+    Ok(res)
+}
+
+/// Add a game to the end of the log. Returns its number in the log, starting at 1.
+pub fn append(path: impl AsRef<Path>, game: &Game) -> Result<usize, Box<dyn Error>> {
+    let mut f = OpenOptions::new()
+        .create(true)
+        .read(true)
+        .append(true)
+        .open(path)?;
+    // Count the games without parsing them.
+    let mut count = 0;
+    for line in BufReader::new(&f).lines() {
+        if !line?.trim().is_empty() {
+            count += 1;
+        }
+    }
+    writeln!(f, "{}", serde_json::to_string(game)?)?;
+    Ok(count + 1)
+}
+
+/// Parse a player argument like "alice=cat,dog,fish" or "alice=@words.txt". A words file has
+/// words separated by whitespace or commas.
+pub fn parse_player(arg: &str) -> Result<(String, Vec<String>), Box<dyn Error>> {
+    let (name, words) = match arg.split_once('=') {
+        Some(x) => x,
+        None => return Ok((arg.to_owned(), vec![])),
+    };
+    let words = match words.strip_prefix('@') {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => words.to_owned(),
+    };
+    let words = words
+        .split(|ch: char| ch == ',' || ch.is_whitespace())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect();
+    Ok((name.to_owned(), words))
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Local;
+
+    use super::{append, load, parse_player, Game};
+    use crate::dictionary::build_dictionary;
+    use crate::wordsearch::Scoring;
+
+    const BOARD: &str = "cats\nxxxx\nxxxx\nxxxx\n";
+
+    #[test]
+    fn shared_words_do_not_count() {
+        let dict = build_dictionary(vec!["cat", "cats", "act"]);
+        let game = Game::new(
+            Local::now(),
+            BOARD,
+            vec![
+                (
+                    "alice".to_owned(),
+                    vec!["cat".to_owned(), "cats".to_owned()],
+                ),
+                ("bob".to_owned(), vec!["cat".to_owned(), "dog".to_owned()]),
+            ],
+            &dict,
+            &Scoring::default(),
+        )
+        .unwrap();
+        assert_eq!(game.board, "CATS\nXXXX\nXXXX\nXXXX\n");
+        assert_eq!(game.total_words, 2);
+        assert_eq!(game.players[0].score, 1);
+        assert_eq!(game.players[1].score, 0);
+    }
+
+    #[test]
+    fn players() {
+        assert_eq!(
+            parse_player("alice=Cat, dog fish").unwrap(),
+            (
+                "alice".to_owned(),
+                vec!["cat".to_owned(), "dog".to_owned(), "fish".to_owned()]
+            )
+        );
+        assert_eq!(parse_player("bob").unwrap(), ("bob".to_owned(), vec![]));
+    }
+
+    #[test]
+    fn round_trip() {
+        let dict = build_dictionary(vec!["cat"]);
+        let path = std::env::temp_dir().join(format!("games-{}.jsonl", std::process::id()));
+        let game = Game::new(Local::now(), BOARD, vec![], &dict, &Scoring::default()).unwrap();
+        assert_eq!(append(&path, &game).unwrap(), 1);
+        assert_eq!(append(&path, &game).unwrap(), 2);
+        let games = load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(games, vec![game.clone(), game]);
+    }
+}
@@ -0,0 +1,212 @@
+use std::error::Error;
+
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+
+use boggle_ocr::config::Settings;
+use boggle_ocr::dictionary::{self, Definitions, Dictionary};
+use boggle_ocr::query;
+use boggle_ocr::wordsearch::{self, Board, Pos, Scoring};
+
+use super::options::InteractiveOptions;
+use super::settings;
+
+const HELP: &str = "commands:
+  board LETTERS     solve a board, e.g. \"board abcde fghij klmno pqrst uvwxy\"
+  words [N|all]     show the N best words on the board (default 20)
+  check WORD        check whether WORD is on the board
+  define WORD       look up a definition
+  pattern PATTERN   find words like PATTERN, '?' is any letter and '*' is any letters
+  anagram LETTERS   find words that use all of LETTERS, '?' is a blank
+  dict PATH         switch to another dictionary
+  defs PATH         switch to another definitions file
+  help              show this message
+  quit              exit";
+
+struct Session {
+    dict: Dictionary,
+    defs: Definitions,
+    scoring: Scoring,
+    board: Option<Solved>,
+}
+
+struct Solved {
+    // The board as it was typed, to read it again if the dictionary's alphabet changes.
+    raw: String,
+    board: Board,
+    words: Vec<(String, Vec<Pos>)>,
+}
+
+pub fn run(opts: InteractiveOptions, settings: &Settings) -> Result<(), Box<dyn Error>> {
+    let defs = match dictionary::open_defs(&opts.defs_dict) {
+        Ok(defs) => defs,
+        // Definitions are optional unless they were asked for.
+        Err(err) if opts.defs_dict.is_none() => {
+            eprintln!("not showing definitions: {}", err);
+            Definitions::new()
+        }
+        Err(err) => return Err(err),
+    };
+    let mut session = Session {
+        dict: dictionary::open_magic(&opts.dict)?,
+        defs,
+        scoring: settings::scoring(settings)?,
+        board: None,
+    };
+
+    let history = std::env::var_os("HOME")
+        .map(|home| std::path::Path::new(&home).join(".boggle-ocr-history"));
+    let mut rl = DefaultEditor::new()?;
+    if let Some(path) = &history {
+        // There's no history the first time.
+        let _ = rl.load_history(path);
+    }
+
+    println!("type \"help\" for a list of commands");
+    loop {
+        let line = match rl.readline("boggle> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => break,
+            Err(err) => return Err(Box::new(err)),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        rl.add_history_entry(line)?;
+        match session.exec(line) {
+            Ok(true) => (),
+            Ok(false) => break,
+            Err(err) => println!("error: {}", err),
+        };
+    }
+
+    if let Some(path) = &history {
+        rl.save_history(path)?;
+    }
+    Ok(())
+}
+
+impl Session {
+    // Run one command. Returns false when it's time to quit.
+    fn exec(&mut self, line: &str) -> Result<bool, Box<dyn Error>> {
+        let (cmd, arg) = match line.split_once(char::is_whitespace) {
+            Some((cmd, arg)) => (cmd, arg.trim()),
+            None => (line, ""),
+        };
+        match cmd {
+            "board" | "b" => {
+                let raw = arg.replace(',', " ");
+                let board = wordsearch::boggled_in(&raw, &self.dict.alphabet)?;
+                let words = self.find_words(&board);
+                let total_score: u32 = words.iter().map(|(w, _)| self.scoring.score(w)).sum();
+                for row in board.rows_in(&self.dict.alphabet) {
+                    println!("  {}", row);
+                }
+                println!("found {} words, {} points", words.len(), total_score);
+                self.board = Some(Solved { raw, board, words });
+                self.show_words(Some(20));
+            }
+            "words" | "w" => match arg {
+                "" => self.show_words(Some(20)),
+                "all" => self.show_words(None),
+                n => self.show_words(Some(n.parse()?)),
+            },
+            "check" | "c" => {
+                let word = arg.to_lowercase();
+                let Solved { board, words, .. } = match &self.board {
+                    Some(b) => b,
+                    None => return Err("no board yet, use \"board\" first".into()),
+                };
+                match words.iter().find(|(w, _)| *w == word) {
+                    Some((w, path)) => println!(
+                        "{} is on the board for {} points: {}",
+                        w,
+                        self.scoring.score(w),
+                        format_path(path)
+                    ),
+                    None => match (
+                        self.dict.contains(&word),
+                        wordsearch::find_path(board, &self.dict.alphabet, &word),
+                    ) {
+                        (false, _) => println!("{} is not a word", word),
+                        (true, Some(_)) => println!("{} is too short", word),
+                        (true, None) => println!("{} is not on the board", word),
+                    },
+                };
+            }
+            "define" | "d" => {
+                // A note from the overlay wins over the definition, and says why a word is banned.
+                let note = self.dict.note(arg);
+                match (
+                    self.dict.contains(arg),
+                    note.or(self.defs.get(&arg.to_lowercase())),
+                ) {
+                    (true, Some(def)) => println!("{}: {}", arg, def),
+                    (true, None) => println!("{} is a word", arg),
+                    (false, Some(note)) => println!("{} is not a word: {}", arg, note),
+                    (false, None) => println!("{} is not a word", arg),
+                }
+            }
+            "pattern" | "p" => print_list(query::pattern(&self.dict, arg)),
+            "anagram" | "a" => print_list(query::anagrams(&self.dict, arg)),
+            "dict" => {
+                self.dict = dictionary::open_magic(&Some(arg.to_owned()))?;
+                println!("loaded {}", arg);
+                // The board's tiles depend on the alphabet, so read it again.
+                if let Some(Solved { raw, .. }) = self.board.take() {
+                    match wordsearch::boggled_in(&raw, &self.dict.alphabet) {
+                        Ok(board) => {
+                            let words = self.find_words(&board);
+                            self.board = Some(Solved { raw, board, words });
+                        }
+                        Err(err) => println!("cleared the board: {}", err),
+                    }
+                }
+            }
+            "defs" => {
+                self.defs = dictionary::open_defs_path(arg)?;
+                println!("loaded {} definitions", self.defs.len());
+            }
+            "help" | "h" | "?" => println!("{}", HELP),
+            "quit" | "q" | "exit" => return Ok(false),
+            _ => println!("unknown command {:?}, try \"help\"", cmd),
+        };
+        Ok(true)
+    }
+
+    /// The words on the board that count with the house scoring rules.
+    fn find_words(&self, board: &Board) -> Vec<(String, Vec<Pos>)> {
+        wordsearch::find_word_paths(&self.dict, board)
+            .into_iter()
+            .filter(|(w, _)| self.scoring.counts(w))
+            .collect()
+    }
+
+    fn show_words(&self, count: Option<usize>) {
+        let words = match &self.board {
+            Some(solved) => &solved.words,
+            None => {
+                println!("no board yet, use \"board\" first");
+                return;
+            }
+        };
+        let words: Vec<String> = words.iter().map(|(w, _)| w.clone()).collect();
+        for (w, s) in wordsearch::best_words(&words, &self.scoring, count) {
+            let def = self.defs.get(&w).unwrap_or("");
+            println!("  {s:2} {w:13} {def}");
+        }
+    }
+}
+
+fn format_path(path: &[Pos]) -> String {
+    let path: Vec<String> = path.iter().map(|(i, j)| format!("{i}:{j}")).collect();
+    path.join(" ")
+}
+
+fn print_list(words: Vec<String>) {
+    println!("{} words", words.len());
+    for chunk in words.chunks(8) {
+        println!("  {}", chunk.join(" "));
+    }
+}
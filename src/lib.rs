@@ -0,0 +1,70 @@
+//! Word games on top of a compact word list: solve Boggle boards, filter Wordle guesses, and look
+//! words up.
+//!
+//! Load a dictionary with [`open_magic`] or [`read`] (or build one from words with [`build`]),
+//! parse a board with [`boggled`], and find its words with [`solve`]:
+//!
+//! ```
+//! use boggle_ocr::{boggled, build_dictionary, solve, SolveOptions};
+//!
+//! let dict = build_dictionary(vec!["cat", "cats", "scat"]);
+//! let board = boggled("cats\nxxxx\nxxxx\nxxxx\n")?;
+//! let solution = solve(&dict, &board, &SolveOptions::default());
+//! assert_eq!(solution.total_words, 2);
+//! assert_eq!(solution.total_score, 2);
+//! # Ok::<(), boggle_ocr::WSError>(())
+//! ```
+//!
+//! [`Scoring`] has house rules for which words count and what they're worth, and
+//! [`solve_wordle`] finds the words that fit a Wordle guess's [`wordle_clues`]. [`client`] talks
+//! to a running `boggle-ocr serve`.
+//!
+//! Everything else is shared with the command line and the server, and can change in any
+//! release.
+
+// The original tests predate these lints.
+#![cfg_attr(test, allow(clippy::useless_vec, clippy::redundant_static_lifetimes))]
+
+pub use alphabet::Alphabet;
+pub use dictionary::{build, build_dictionary, open_magic, read, DError, Definitions, Dictionary};
+pub use wordle::{clues as wordle_clues, run as solve_wordle, Clue, ClueError};
+pub use wordsearch::{
+    boggled, boggled_in, solve, Board, Pos, Scoring, Solution, SolveOptions, SolvedWord, WSError,
+};
+
+pub mod api;
+pub mod client;
+
+#[doc(hidden)]
+pub mod alphabet;
+#[doc(hidden)]
+pub mod boards;
+#[doc(hidden)]
+pub mod config;
+mod definitions;
+#[doc(hidden)]
+pub mod dice;
+#[doc(hidden)]
+pub mod dictionary;
+#[doc(hidden)]
+pub mod diff;
+#[doc(hidden)]
+pub mod frequency;
+#[doc(hidden)]
+pub mod games;
+#[doc(hidden)]
+pub mod locate;
+#[doc(hidden)]
+pub mod optimize;
+#[doc(hidden)]
+pub mod overlay;
+#[doc(hidden)]
+pub mod query;
+#[doc(hidden)]
+pub mod review;
+#[doc(hidden)]
+pub mod stats;
+#[doc(hidden)]
+pub mod wordle;
+#[doc(hidden)]
+pub mod wordsearch;
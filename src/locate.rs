@@ -0,0 +1,230 @@
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use super::config::{Settings, Source};
+
+// Where to look for dictionaries and the files that go with them, in order:
+//
+//   1. the current directory
+//   2. each directory in $BOGGLE_OCR_PATH (separated like $PATH)
+//   3. the "dictionary.search_path" list in the config files
+//   4. $XDG_DATA_HOME/boggle-ocr, or ~/.local/share/boggle-ocr
+//   5. boggle-ocr in each of $XDG_DATA_DIRS, or /usr/local/share and /usr/share
+//
+// $BOGGLE_OCR_DICT, or "dictionary.dict" in a config file, picks a dictionary without searching.
+// The library never reads config files itself. The command line passes its settings to
+// `use_settings`, and until then only the environment counts.
+
+pub use super::config::DICT_VAR;
+pub const PATH_VAR: &str = "BOGGLE_OCR_PATH";
+
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+static SETTINGS: OnceLock<&'static Settings> = OnceLock::new();
+
+/// Say which files are tried and loaded, on stderr.
+pub fn set_verbose(verbose: bool) {
+    VERBOSE.store(verbose, Ordering::Relaxed);
+}
+
+/// Search with these settings from now on, instead of only the environment. Only the first call
+/// counts.
+pub fn use_settings(settings: &'static Settings) {
+    let _ = SETTINGS.set(settings);
+}
+
+pub fn trace(msg: impl Display) {
+    if VERBOSE.load(Ordering::Relaxed) {
+        eprintln!("{}", msg);
+    }
+}
+
+/// Why a directory is searched, or why a file was picked.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Origin {
+    CurrentDir,
+    Env(&'static str),
+    Config(PathBuf),
+    XdgDataHome,
+    XdgDataDirs,
+}
+
+impl Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Origin::CurrentDir => write!(f, "current directory"),
+            Origin::Env(var) => write!(f, "${}", var),
+            Origin::Config(path) => write!(f, "{}", path.display()),
+            Origin::XdgDataHome => write!(f, "XDG data home"),
+            Origin::XdgDataDirs => write!(f, "XDG data dirs"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SearchPath<'a> {
+    settings: &'a Settings,
+    dirs: Vec<(PathBuf, Origin)>,
+}
+
+impl SearchPath<'static> {
+    /// The search path from the environment, and the settings from `use_settings` if there are
+    /// any.
+    pub fn from_env() -> Self {
+        static ENV_ONLY: OnceLock<Settings> = OnceLock::new();
+        let settings = match SETTINGS.get() {
+            Some(settings) => *settings,
+            None => ENV_ONLY.get_or_init(|| Settings::new(vec![], |var| std::env::var(var).ok())),
+        };
+        Self::new(|var| std::env::var(var).ok(), settings)
+    }
+}
+
+impl<'a> SearchPath<'a> {
+    fn new(var: impl Fn(&str) -> Option<String>, settings: &'a Settings) -> Self {
+        let var = |name: &str| var(name).filter(|v| !v.is_empty());
+
+        let mut dirs = vec![(PathBuf::from("."), Origin::CurrentDir)];
+        if let Some(path) = var(PATH_VAR) {
+            for dir in std::env::split_paths(&path) {
+                dirs.push((dir, Origin::Env(PATH_VAR)));
+            }
+        }
+        if let Some((paths, Source::File(config))) = settings.paths("dictionary.search_path") {
+            for dir in paths {
+                dirs.push((dir, Origin::Config(config.clone())));
+            }
+        }
+        let data_home = match (var("XDG_DATA_HOME"), var("HOME")) {
+            (Some(dir), _) => Some(PathBuf::from(dir)),
+            (None, Some(home)) => Some(Path::new(&home).join(".local/share")),
+            (None, None) => None,
+        };
+        if let Some(dir) = data_home {
+            dirs.push((dir.join("boggle-ocr"), Origin::XdgDataHome));
+        }
+        let data_dirs = var("XDG_DATA_DIRS").unwrap_or("/usr/local/share:/usr/share".to_owned());
+        for dir in data_dirs.split(':').filter(|d| !d.is_empty()) {
+            dirs.push((Path::new(dir).join("boggle-ocr"), Origin::XdgDataDirs));
+        }
+        Self { settings, dirs }
+    }
+
+    /// A file that's picked by a setting, like "dictionary.dict", instead of searched for.
+    pub fn configured(&self, name: &str) -> Option<(PathBuf, Origin)> {
+        let (path, origin) = match self.settings.path(name)? {
+            (path, Source::Env(var)) => (path, Origin::Env(var)),
+            (path, Source::File(config)) => (path, Origin::Config(config)),
+            (_, Source::Default) => return None,
+        };
+        trace(format!("using {} ({})", path.display(), origin));
+        Some((path, origin))
+    }
+
+    pub fn dirs(&self) -> &[(PathBuf, Origin)] {
+        &self.dirs
+    }
+
+    /// Look for a file in each directory. Returns the first one that exists, or all of the paths
+    /// that were tried.
+    pub fn find(&self, name: &str) -> Result<(PathBuf, Origin), Vec<PathBuf>> {
+        let mut tried = vec![];
+        for (dir, origin) in &self.dirs {
+            let path = match origin {
+                Origin::CurrentDir => PathBuf::from(name),
+                _ => dir.join(name),
+            };
+            if path.is_file() {
+                trace(format!("found {} ({})", path.display(), origin));
+                return Ok((path, origin.clone()));
+            }
+            trace(format!("no {} ({})", path.display(), origin));
+            tried.push(path);
+        }
+        Err(tried)
+    }
+}
+
+/// An optional file, and whether it was asked for or just happened to be on the search path.
+#[derive(Debug, PartialEq)]
+pub enum Optional {
+    Configured(PathBuf),
+    Found(PathBuf),
+}
+
+/// The file picked by a setting, or else the named file if it's on the search path.
+pub fn find_optional(setting: &str, name: &str) -> Option<Optional> {
+    let search = SearchPath::from_env();
+    match search.configured(setting) {
+        Some((path, _)) => Some(Optional::Configured(path)),
+        None => search
+            .find(name)
+            .ok()
+            .map(|(path, _)| Optional::Found(path)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::{Origin, SearchPath, DICT_VAR, PATH_VAR};
+    use crate::config::{Config, Settings};
+
+    #[test]
+    fn search_path() {
+        let config = Config::parse(
+            "/home/me/.config/boggle-ocr/config.toml",
+            "[dictionary]\nsearch_path = [\"words\"]\n",
+        )
+        .unwrap();
+        let env = |var: &str| match var {
+            PATH_VAR => Some("/a:/b".to_owned()),
+            "HOME" => Some("/home/me".to_owned()),
+            "XDG_DATA_DIRS" => Some("/opt/share:".to_owned()),
+            _ => None,
+        };
+        let from_config = Origin::Config(config.path().to_owned());
+        let settings = Settings::new(vec![config], env);
+        let search = SearchPath::new(env, &settings);
+        let dirs: Vec<(&str, &Origin)> = search
+            .dirs()
+            .iter()
+            .map(|(dir, origin)| (dir.to_str().unwrap(), origin))
+            .collect();
+        assert_eq!(
+            dirs,
+            vec![
+                (".", &Origin::CurrentDir),
+                ("/a", &Origin::Env(PATH_VAR)),
+                ("/b", &Origin::Env(PATH_VAR)),
+                ("/home/me/.config/boggle-ocr/words", &from_config),
+                ("/home/me/.local/share/boggle-ocr", &Origin::XdgDataHome),
+                ("/opt/share/boggle-ocr", &Origin::XdgDataDirs),
+            ]
+        );
+        assert_eq!(search.configured("dictionary.dict"), None);
+
+        let env = |var: &str| match var {
+            DICT_VAR => Some("my.dict".to_owned()),
+            _ => None,
+        };
+        let settings = Settings::new(vec![], env);
+        let search = SearchPath::new(env, &settings);
+        assert_eq!(
+            search.configured("dictionary.dict"),
+            Some((PathBuf::from("my.dict"), Origin::Env(DICT_VAR)))
+        );
+        // Without settings from the command line, the config files aren't read.
+        assert_eq!(SearchPath::from_env().settings.files().count(), 0);
+        assert_eq!(
+            search.find("no-such-file.dict"),
+            Err(vec![
+                PathBuf::from("no-such-file.dict"),
+                PathBuf::from("/usr/local/share/boggle-ocr/no-such-file.dict"),
+                PathBuf::from("/usr/share/boggle-ocr/no-such-file.dict"),
+            ])
+        );
+    }
+}
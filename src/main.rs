@@ -1,189 +1,1027 @@
 use std::error::Error;
 use std::fs::OpenOptions;
 use std::io::BufWriter;
+use std::sync::Arc;
 
-use dictionary::Definitions;
+use boggle_ocr::alphabet::{self, Alphabet};
+use boggle_ocr::api::{BoardInput, SolveRequest};
+use boggle_ocr::client::{Client, ClientError};
+use boggle_ocr::config::{ConfigError, Settings, Source};
+use boggle_ocr::dictionary::{self, Definitions};
+use boggle_ocr::wordsearch::{self, Scoring};
+use boggle_ocr::{boards, dice, diff, frequency, games, optimize, overlay, review, stats};
+use boggle_ocr::{locate, wordle};
+use chrono::{DateTime, Local, NaiveDateTime};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::Serialize;
 
-pub mod dictionary;
+// The command line. Everything else is in the library.
+mod cache;
+mod interactive;
+mod metrics;
+mod openapi;
 mod options;
+mod output;
+mod play;
+mod settings;
 mod webserver;
-mod wordle;
-mod wordsearch;
 
 // detect dice: https://stackoverflow.com/questions/55169645/square-detection-in-image
 // opencv rust: https://docs.rs/opencv/0.62.0/opencv/index.html
 
 fn main() {
     use options::Commands::*;
-    if let Err(err) = match options::parse() {
-        Boggle(opts) => boggle(opts),
-        Wordle(opts) => wordle(opts),
-        Summarize(opts) => summarize(opts),
+    let cli = options::parse();
+    locate::set_verbose(cli.verbose);
+    if let Err(err) = match cli.command {
+        Boggle(opts) => with_settings(|settings| boggle(opts, settings)),
+        Wordle(opts) => with_settings(|settings| wordle(opts, settings)),
+        Summarize(opts) => with_settings(|settings| summarize(opts, settings)),
         Compile(opts) => compile(opts),
-        Serve(opts) => webserver::serve(opts),
+        Dict(opts) => with_settings(|settings| dict(opts, settings)),
+        Serve(opts) => with_settings(|settings| webserver::serve(opts, settings)),
+        Generate(opts) => with_settings(|settings| generate(opts, settings)),
+        Optimize(opts) => with_settings(|settings| optimize(opts, settings)),
+        Game(opts) => with_settings(|settings| game(opts, settings)),
+        Review(opts) => with_settings(|settings| review(opts, settings)),
+        Stats(opts) => with_settings(|settings| stats(opts, settings)),
+        Interactive(opts) => with_settings(|settings| interactive::run(opts, settings)),
+        Play(opts) => with_settings(|settings| play::run(opts, settings)),
+        Config(opts) => config(opts),
     } {
-        println!("error: {}", err);
-        std::process::exit(1);
+        eprintln!("error: {}", err);
+        std::process::exit(exit_code(err.as_ref()));
     }
 }
 
 type Res = Result<(), Box<dyn std::error::Error>>;
 
-fn boggle(opts: options::BoggleOptions) -> Res {
-    let dict = dictionary::open_magic(&opts.dict)?;
+// Run a command that uses the config files. They're only read for these commands, which also
+// search for dictionaries with them.
+fn with_settings(command: impl FnOnce(&Settings) -> Res) -> Res {
+    let settings = Settings::shared()?;
+    locate::use_settings(settings);
+    command(settings)
+}
+
+fn boggle(opts: options::BoggleOptions, settings: &Settings) -> Res {
+    let format = settings::or_setting(opts.format, settings, "output.format")?;
+    let scoring = settings::scoring(settings)?;
+    let limit = match opts.show_all {
+        true => None,
+        false => Some(settings::count(settings, "output.limit")?),
+    };
+    let dict = overlay::apply(dictionary::open_magic(&opts.dict)?, &opts.overlay)?;
     let defs = match opts.defs {
         false => Definitions::new(),
         true => dictionary::open_defs(&opts.defs_dict)?,
     };
-    wordsearch::find_all_in_file(&opts.board, dict, defs, opts.show_all)
+    let filter = frequency::Filter::open(&opts.freq, opts.common, &dict)?;
+    let raw_board = boards::read_board(opts.board.as_deref(), opts.letters.as_deref())?;
+    let board = wordsearch::boggled_in(&raw_board, &dict.alphabet)?;
+
+    let t = std::time::Instant::now();
+    let solve_opts = wordsearch::SolveOptions {
+        defs: Some(&defs),
+        filter: Some(&filter),
+        limit: None,
+        scoring: Some(&scoring),
+    };
+    let solution = wordsearch::solve(&dict, &board, &solve_opts);
+    dictionary::report_time("find_words", t);
+
+    print_solution(&raw_board, &solution, limit, format)
 }
 
-fn wordle(opts: options::WordleOptions) -> Res {
-    let dict = dictionary::open_magic(&opts.dict)?;
-    let clues = wordle_clues(opts)?;
-    let words = wordle::run(&clues, &dict);
-    println!("{} words are possible!", words.len());
-    if words.len() < 20 {
-        for w in words {
-            println!("- {}", w);
-        }
-    } else {
-        let mut counts = [0; 26];
-        for w in words {
-            for c in w.chars() {
-                counts[dictionary::Letter::new(c).i()] += 1;
+fn print_solution(
+    raw_board: &str,
+    solution: &wordsearch::Solution,
+    limit: Option<usize>,
+    format: options::OutputFormat,
+) -> Res {
+    use options::OutputFormat;
+    use output::table_row;
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(solution)?),
+        OutputFormat::Csv | OutputFormat::Tsv => {
+            println!("{}", table_row(format, &["word", "score", "path", "def"]));
+            for w in &solution.words {
+                let path: Vec<String> = w.path.iter().map(|(i, j)| format!("{i}:{j}")).collect();
+                println!(
+                    "{}",
+                    table_row(
+                        format,
+                        &[
+                            w.word.clone(),
+                            w.score.to_string(),
+                            path.join(" "),
+                            w.def.clone().unwrap_or_default(),
+                        ]
+                    )
+                );
             }
         }
-        let mut counts: Vec<(usize, u32)> = counts.into_iter().enumerate().collect();
-        // sort from max to min.
-        counts.sort_by(|(_, a), (_, b)| b.cmp(a));
-        println!("most common letters:");
-        for (i, count) in &counts[..10] {
-            println!(" {}: {}", dictionary::Letter::for_i(*i).ch(), count);
+        OutputFormat::Text => {
+            println!("{}", raw_board);
+            println!(
+                "found {} words, {} points, {:.2} per word",
+                solution.total_words,
+                solution.total_score,
+                solution.total_score as f32 / solution.total_words as f32,
+            );
+            println!("best words:");
+            let max_count = limit.unwrap_or(solution.words.len());
+            for w in solution.words.iter().take(max_count) {
+                let (s, w, def) = (w.score, &w.word, w.def.as_deref().unwrap_or(""));
+                println!("  {s:2} {w:13} {def}");
+            }
         }
-    }
+    };
     Ok(())
 }
 
-fn wordle_clues(opts: options::WordleOptions) -> Result<Vec<wordle::Clue>, GenericError> {
-    let mut res = vec![];
-    if let Some(pattern) = opts.pattern {
-        for (i, ch) in pattern.chars().enumerate() {
-            if i > 4 {
-                return Err(GenericError(format!("invalid pattern \"{}\"", pattern)));
-            }
-            if ch == '-' {
-                continue;
-            }
-            res.push(wordle::Clue::Green(ch, i));
+fn wordle(opts: options::WordleOptions, settings: &Settings) -> Res {
+    use options::OutputFormat;
+
+    let dict = overlay::apply(dictionary::open_magic(&opts.dict)?, &opts.overlay)?;
+    let format = settings::or_setting(opts.format, settings, "output.format")?;
+    let filter = frequency::Filter::open(&opts.freq, opts.common, &dict)?;
+    let clues = wordle::clues(
+        &dict.alphabet,
+        opts.pattern.as_deref(),
+        opts.include.as_deref(),
+        opts.exclude.as_deref(),
+    )?;
+    let mut words = wordle::run(&clues, &dict);
+    words.retain(|w| filter.keep(&dict, w));
+    words.sort();
+    // Most common first, if there's a frequency file.
+    words.sort_by_key(|w| filter.tier(&dict, w));
+
+    let mut counts = vec![0; dict.alphabet.len()];
+    for w in &words {
+        for l in dict.alphabet.letters(w).unwrap_or_default() {
+            counts[l.i()] += 1;
         }
     }
-    if let Some(s) = opts.include {
-        for ch in s.chars() {
-            res.push(wordle::Clue::Yellow(ch));
+    let mut counts: Vec<(usize, u32)> = counts.into_iter().enumerate().collect();
+    // sort from max to min.
+    counts.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+    match format {
+        OutputFormat::Json => {
+            let letters: Vec<WordleLetter> = counts
+                .iter()
+                .filter(|(_, count)| *count > 0)
+                .map(|(i, count)| WordleLetter {
+                    letter: dict.alphabet.text(dictionary::Letter::for_i(*i)).to_owned(),
+                    count: *count,
+                })
+                .collect();
+            let res = WordleResult {
+                count: words.len(),
+                words,
+                letters,
+            };
+            println!("{}", serde_json::to_string_pretty(&res)?);
         }
-    }
-    if let Some(s) = opts.exclude {
-        for ch in s.chars() {
-            res.push(wordle::Clue::Gray(ch));
+        OutputFormat::Csv | OutputFormat::Tsv => {
+            println!("word");
+            for w in words {
+                println!("{}", w);
+            }
         }
-    }
-    Ok(res)
+        OutputFormat::Text => {
+            println!("{} words are possible!", words.len());
+            if words.len() < 20 {
+                for w in words {
+                    println!("- {}", w);
+                }
+            } else {
+                println!("most common letters:");
+                for (i, count) in &counts[..10] {
+                    println!(
+                        " {}: {}",
+                        dict.alphabet.text(dictionary::Letter::for_i(*i)),
+                        count
+                    );
+                }
+            }
+        }
+    };
+    Ok(())
 }
 
-fn summarize(opts: options::SummarizeOptions) -> Res {
-    let dict = dictionary::open_magic(&opts.dict)?;
+#[derive(Serialize)]
+struct WordleResult {
+    count: usize,
+    words: Vec<String>,
+    /// How many of the possible words have each letter, most common first.
+    letters: Vec<WordleLetter>,
+}
+
+#[derive(Serialize)]
+struct WordleLetter {
+    letter: String,
+    count: u32,
+}
+
+fn summarize(opts: options::SummarizeOptions, settings: &Settings) -> Res {
+    use options::OutputFormat;
+
+    let format = settings::or_setting(opts.format, settings, "output.format")?;
+    let scoring = settings::scoring(settings)?;
+    let solve: Box<dyn Fn(&str) -> BoardTotals> = match &opts.server {
+        Some(url) => {
+            let client = Client::new(url)?;
+            let dict = opts.dict.clone();
+            let scoring = scoring.clone();
+            Box::new(move |board| summarize_remote(&client, board, &dict, &scoring))
+        }
+        None => {
+            let dict = dictionary::open_magic(&opts.dict)?;
+            Box::new(move |board| summarize_board(board, &dict, &scoring))
+        }
+    };
     let mut total_words = 0;
     let mut total_score = 0;
+    let mut boards = vec![];
+    for path in &opts.boards {
+        match boards::read_boards(path) {
+            Ok(b) => boards.extend(b),
+            Err(err) => eprintln!("{}: {}", path, err),
+        };
+    }
+    for letters in &opts.letters {
+        boards.push((letters.clone(), boards::from_letters(letters)));
+    }
     let mut scores = Vec::new();
-    for board in opts.boards {
-        match summarize_board(&board, &dict) {
-            Ok((msg, words, score)) => {
+    for (board, raw_board) in boards {
+        match solve(&raw_board) {
+            Ok((words, score)) => {
                 total_words += words;
                 total_score += score;
-                scores.push((board, msg, words, score));
+                scores.push(BoardSummary {
+                    board,
+                    words,
+                    score,
+                    points_per_word: points_per_word(score, words),
+                });
             }
-            Err(err) => println!("{}: {}", board, err),
+            Err(err) => eprintln!("{}: {}", board, err),
         };
     }
     match opts.sort {
         options::SortOrder::None => (),
         options::SortOrder::Name => {
-            scores.sort_by(|(a, _, _, _), (b, _, _, _)| a.partial_cmp(b).unwrap());
+            scores.sort_by(|a, b| a.board.cmp(&b.board));
         }
         options::SortOrder::Words => {
-            scores.sort_by(|(_, _, a, _), (_, _, b, _)| a.partial_cmp(b).unwrap());
+            scores.sort_by_key(|s| s.words);
         }
         options::SortOrder::Score => {
-            scores.sort_by(|(_, _, _, a), (_, _, _, b)| a.partial_cmp(b).unwrap());
+            scores.sort_by_key(|s| s.score);
         }
     };
-    for (board, msg, _, _) in &scores {
-        println!("{}: {}", board, msg);
+    if scores.is_empty() {
+        return Err(Box::new(CliError::Failed(
+            "no boards were solved".to_owned(),
+        )));
     }
-    println!(
-        "avg words: {}, avg score: {}, {:.2} points per word",
-        total_words / scores.len(),
-        total_score / scores.len() as u32,
-        total_score as f64 / total_words as f64
-    );
+    let summary = Summary {
+        avg_words: total_words / scores.len(),
+        avg_score: total_score / scores.len() as u32,
+        points_per_word: points_per_word(total_score, total_words),
+        boards: scores,
+    };
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&summary)?),
+        format @ (OutputFormat::Csv | OutputFormat::Tsv) => {
+            use output::table_row;
+            println!(
+                "{}",
+                table_row(format, &["board", "words", "score", "points_per_word"])
+            );
+            for s in &summary.boards {
+                println!(
+                    "{}",
+                    table_row(
+                        format,
+                        &[
+                            s.board.clone(),
+                            s.words.to_string(),
+                            s.score.to_string(),
+                            format!("{:.2}", s.points_per_word),
+                        ]
+                    )
+                );
+            }
+        }
+        OutputFormat::Text => {
+            for s in &summary.boards {
+                println!(
+                    "{}: found {} words, {} points, {:.2} per word",
+                    s.board, s.words, s.score, s.points_per_word
+                );
+            }
+            println!(
+                "avg words: {}, avg score: {}, {:.2} points per word",
+                summary.avg_words, summary.avg_score, summary.points_per_word
+            );
+        }
+    };
     Ok(())
 }
 
+#[derive(Serialize)]
+struct Summary {
+    boards: Vec<BoardSummary>,
+    avg_words: usize,
+    avg_score: u32,
+    points_per_word: f64,
+}
+
+#[derive(Serialize)]
+struct BoardSummary {
+    board: String,
+    words: usize,
+    score: u32,
+    points_per_word: f64,
+}
+
+// The number of words on a board and their total score.
+type BoardTotals = Result<(usize, u32), Box<dyn Error>>;
+
+// 0 for a board without words, rather than NaN.
+fn points_per_word(score: u32, words: usize) -> f64 {
+    match words {
+        0 => 0.0,
+        words => score as f64 / words as f64,
+    }
+}
+
 fn summarize_board(
     board: &str,
     dict: &dictionary::Dictionary,
-) -> Result<(String, usize, u32), Box<dyn Error>> {
-    let board = std::fs::read_to_string(board)?;
-    let lines: Vec<&str> = board.lines().collect();
-    let words = wordsearch::find_boggle_words(&lines, dict, 3);
-    let total_words = words.len();
-    let total_score: u32 = words.iter().map(|w| w.score).sum();
-    let avg_score = total_score as f64 / total_words as f64;
-    Ok((
-        format!(
-            "found {} words, {} points, {:.2} per word",
-            total_words, total_score, avg_score
-        ),
-        total_words,
-        total_score,
-    ))
+    scoring: &wordsearch::Scoring,
+) -> BoardTotals {
+    // The same solver as the server's, so that summarize --server gets the same totals.
+    let board = wordsearch::boggled_in(board, &dict.alphabet)?;
+    let opts = wordsearch::SolveOptions {
+        scoring: Some(scoring),
+        limit: Some(0),
+        ..Default::default()
+    };
+    let solution = wordsearch::solve(dict, &board, &opts);
+    Ok((solution.total_words, solution.total_score))
+}
+
+// Like summarize_board, but the server solves the board with its dictionary named `dict`.
+fn summarize_remote(
+    client: &Client,
+    board: &str,
+    dict: &Option<String>,
+    scoring: &wordsearch::Scoring,
+) -> BoardTotals {
+    let mut request = SolveRequest::new(BoardInput::Text(board.to_owned()));
+    request.dict = dict.clone();
+    request.scoring = Some(scoring.clone());
+    request.limit = Some(0);
+    let res = client.solve(&request)?;
+    Ok((res.total_words, res.total_score))
+}
+
+fn generate(opts: options::GenerateOptions, settings: &Settings) -> Res {
+    let dice = settings::or_setting(opts.dice, settings, "board.dice")?;
+    let mut rng = match opts.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let board = if opts.min_words == 0 && opts.min_score == 0 {
+        dice::roll(dice, &mut rng)
+    } else {
+        let dict = dictionary::open_magic(&opts.dict)?;
+        match dice::generate(
+            dice,
+            &mut rng,
+            &dict,
+            &settings::scoring(settings)?,
+            opts.min_words,
+            opts.min_score,
+            opts.max_tries,
+        ) {
+            Some(board) => board,
+            None => {
+                return Err(Box::new(CliError::Failed(format!(
+                    "no board with {} words and {} points in {} tries",
+                    opts.min_words, opts.min_score, opts.max_tries
+                ))))
+            }
+        }
+    };
+    print!("{}", board);
+    Ok(())
+}
+
+fn optimize(opts: options::OptimizeOptions, settings: &Settings) -> Res {
+    let size = match opts.size {
+        Some(size) => size,
+        None => settings::count(settings, "board.size")?,
+    };
+    if !(4..=6).contains(&size) {
+        return Err(Box::new(CliError::Usage(format!(
+            "--size must be 4, 5, or 6, not {}",
+            size
+        ))));
+    }
+    // Dice from a config file are a default, so they don't win over --size.
+    let dice = match (opts.dice, opts.size, settings.get("board.dice")) {
+        (Some(dice), _, _) => Some(dice),
+        (None, None, Some((_, Source::File(_)))) => {
+            Some(settings::or_setting(None, settings, "board.dice")?)
+        }
+        _ => None,
+    };
+    let dict = dictionary::open_magic(&opts.dict)?;
+    let threads = match opts.threads {
+        Some(n) => n,
+        None => std::thread::available_parallelism()?.get(),
+    };
+    let scoring = settings::scoring(settings)?;
+    let optimizer = optimize::Optimizer {
+        dict: &dict,
+        scoring: &scoring,
+        size,
+        dice,
+        objective: opts.objective,
+        steps: opts.steps,
+        temperature: opts.temperature,
+    };
+    let mut start = None;
+    if let Some(path) = &opts.checkpoint {
+        if std::path::Path::new(path).exists() {
+            let board = std::fs::read_to_string(path)?;
+            match optimizer.check_start(&board) {
+                Ok(()) => start = Some(board),
+                // Don't overwrite a checkpoint from a different search by accident.
+                Err(err) if !opts.overwrite_checkpoint => {
+                    return Err(Box::new(CliError::Usage(format!(
+                        "can't resume from {}: {}. Pass --overwrite-checkpoint to start over",
+                        path, err
+                    ))))
+                }
+                Err(err) => eprintln!("{}: {}, starting over", path, err),
+            }
+        }
+    }
+    let best = optimizer.run(
+        threads,
+        opts.restarts,
+        opts.seed,
+        start.as_deref(),
+        |found| {
+            eprintln!("found {} words, {} points", found.words, found.score);
+            if let Some(path) = &opts.checkpoint {
+                if let Err(err) = save_checkpoint(path, &found.board) {
+                    eprintln!("{}: {}", path, err);
+                }
+            }
+        },
+    )?;
+    if let Some(best) = best {
+        println!(
+            "best board: {} words, {} points\n{}",
+            best.words, best.score, best.board
+        );
+    }
+    Ok(())
+}
+
+// Write to a temporary file first so that an interrupted run never leaves a partial board.
+fn save_checkpoint(path: &str, board: &str) -> std::io::Result<()> {
+    let tmp = format!("{}.tmp", path);
+    std::fs::write(&tmp, board)?;
+    std::fs::rename(tmp, path)
+}
+
+fn game(opts: options::GameOptions, settings: &Settings) -> Res {
+    use options::GameCommands::*;
+    let path = opts.games.unwrap_or_else(|| "games.jsonl".to_owned());
+    match opts.command {
+        Add(opts) => game_add(&path, opts, &settings::scoring(settings)?),
+        List => game_list(&path),
+        Show(opts) => game_show(&path, opts),
+        Import(opts) => game_import(&path, opts, &settings::scoring(settings)?),
+    }
+}
+
+fn game_add(path: &str, opts: options::GameAddOptions, scoring: &Scoring) -> Res {
+    let dict = dictionary::open_magic(&opts.dict)?;
+    let date = match opts.date {
+        Some(date) => DateTime::parse_from_rfc3339(&date)?.with_timezone(&Local),
+        None => Local::now(),
+    };
+    let board = boards::read_board(opts.board.as_deref(), opts.letters.as_deref())?;
+    let mut players = vec![];
+    for p in &opts.player {
+        players.push(games::parse_player(p)?);
+    }
+    let game = games::Game::new(date, &board, players, &dict, scoring)?;
+    let id = games::append(path, &game)?;
+    println!(
+        "recorded game {} ({} words, {} points)",
+        id, game.total_words, game.total_score
+    );
+    Ok(())
+}
+
+fn game_list(path: &str) -> Res {
+    for (i, game) in games::load(path)?.iter().enumerate() {
+        let players: Vec<String> = game
+            .players
+            .iter()
+            .map(|p| format!("{} {}", p.name, p.score))
+            .collect();
+        println!(
+            "{:4} {} {:4} words {:5} points  {}",
+            i + 1,
+            game.date.format("%Y-%m-%d %H:%M"),
+            game.total_words,
+            game.total_score,
+            players.join(", ")
+        );
+    }
+    Ok(())
+}
+
+fn game_show(path: &str, opts: options::GameShowOptions) -> Res {
+    let games = games::load(path)?;
+    let game = match opts.id.checked_sub(1).and_then(|i| games.get(i)) {
+        Some(game) => game,
+        None => return Err(Box::new(CliError::NotFound(format!("no game {}", opts.id)))),
+    };
+    println!("game {}, {}", opts.id, game.date.format("%Y-%m-%d %H:%M"));
+    println!("{}", game.board);
+    println!(
+        "{} words, {} points on the board",
+        game.total_words, game.total_score
+    );
+    for p in &game.players {
+        println!("{}: {} points", p.name, p.score);
+        println!("  {}", p.words.join(" "));
+    }
+    Ok(())
+}
+
+fn game_import(path: &str, opts: options::GameImportOptions, scoring: &Scoring) -> Res {
+    let dict = dictionary::open_magic(&opts.dict)?;
+    let mut failed = 0;
+    for board_path in &opts.boards {
+        let stem = std::path::Path::new(board_path)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default();
+        let date = match NaiveDateTime::parse_from_str(stem, "%Y-%m-%d-%H-%M-%S") {
+            Ok(date) => match date.and_local_timezone(Local).earliest() {
+                Some(date) => date,
+                None => Local::now(),
+            },
+            Err(_) => DateTime::from(std::fs::metadata(board_path)?.modified()?),
+        };
+        let board = std::fs::read_to_string(board_path)?;
+        match games::Game::new(date, &board, vec![], &dict, scoring) {
+            Ok(game) => {
+                games::append(path, &game)?;
+            }
+            Err(err) => {
+                eprintln!("{}: {}", board_path, err);
+                failed += 1;
+            }
+        };
+    }
+    if failed > 0 {
+        return Err(Box::new(CliError::BadInput(format!(
+            "{} of {} boards couldn't be imported",
+            failed,
+            opts.boards.len()
+        ))));
+    }
+    Ok(())
+}
+
+fn review(opts: options::ReviewOptions, settings: &Settings) -> Res {
+    let scoring = settings::scoring(settings)?;
+    let dict = dictionary::open_magic(&opts.dict)?;
+    let defs = match opts.defs {
+        false => Definitions::new(),
+        true => dictionary::open_defs(&opts.defs_dict)?,
+    };
+    let raw_board = boards::read_board(Some(&opts.board), None)?;
+    let board = wordsearch::boggled_in(&raw_board, &dict.alphabet)?;
+    let mut words = opts.words;
+    if let Some(path) = &opts.words_file {
+        let contents = std::fs::read_to_string(path)?;
+        words.extend(
+            contents
+                .split(|ch: char| ch == ',' || ch.is_whitespace())
+                .filter(|w| !w.is_empty())
+                .map(str::to_owned),
+        );
+    }
+
+    let r = review::review(&dict, &board, &scoring, &words);
+    println!("{}", raw_board);
+    println!(
+        "found {} of {} words, {} of {} points ({:.1}%)",
+        r.found.len(),
+        r.total_words,
+        r.found_score,
+        r.total_score,
+        r.percent()
+    );
+    if !r.invalid.is_empty() {
+        println!("didn't count:");
+        for (w, reason) in &r.invalid {
+            println!("  {w:13} {reason}");
+        }
+    }
+    println!("by length:");
+    for (len, (found, total)) in &r.by_length {
+        println!("  {len:2} letters: {found:3} of {total:3}");
+    }
+    println!("best words missed:");
+    let filter = frequency::Filter::open(&opts.freq, opts.common, &dict)?;
+    let missed = r.missed.iter().filter(|(w, _)| filter.keep(&dict, w));
+    for (w, s) in missed.take(opts.missed) {
+        let def = match defs.get(w) {
+            Some(def) => def.to_owned(),
+            None => "".to_owned(),
+        };
+        println!("  {s:2} {w:13} {def}");
+    }
+    Ok(())
+}
+
+fn stats(opts: options::StatsOptions, settings: &Settings) -> Res {
+    use options::OutputFormat;
+    use options::StatsTable;
+    use output::table_row;
+
+    let dict = dictionary::open_magic(&opts.dict)?;
+    let games = games::load(opts.games.as_deref().unwrap_or("games.jsonl"))?;
+    let scoring = settings::scoring(settings)?;
+    let dice = settings::or_setting(opts.dice, settings, "board.dice")?;
+    let stats = stats::compute(&games, &dict, &scoring, dice, opts.missed);
+    let format = settings::or_setting(opts.format, settings, "output.format")?;
+    let row = |fields: &[String]| println!("{}", table_row(format, fields));
+    let header = |fields: &[&str]| println!("{}", table_row(format, fields));
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&stats)?),
+        OutputFormat::Csv | OutputFormat::Tsv => match opts.table {
+            StatsTable::Players => {
+                header(&["player", "date", "score", "percent"]);
+                for p in &stats.players {
+                    for g in &p.games {
+                        row(&[
+                            p.name.clone(),
+                            g.date.to_rfc3339(),
+                            g.score.to_string(),
+                            format!("{:.1}", g.percent),
+                        ]);
+                    }
+                }
+            }
+            StatsTable::Histogram => {
+                header(&["from", "to", "count"]);
+                for b in &stats.score_histogram {
+                    row(&[b.from.to_string(), b.to.to_string(), b.count.to_string()]);
+                }
+            }
+            StatsTable::Missed => {
+                header(&["word", "score", "boards", "missed"]);
+                for m in &stats.missed {
+                    row(&[
+                        m.word.clone(),
+                        m.score.to_string(),
+                        m.boards.to_string(),
+                        m.missed.to_string(),
+                    ]);
+                }
+            }
+            StatsTable::Letters => {
+                header(&["letter", "observed", "expected", "z"]);
+                for l in &stats.letters {
+                    row(&[
+                        l.letter.to_string(),
+                        l.observed.to_string(),
+                        format!("{:.2}", l.expected),
+                        format!("{:.2}", l.z),
+                    ]);
+                }
+            }
+        },
+        OutputFormat::Text => {
+            println!("{} games", stats.games);
+            for (label, p) in [("points", &stats.score), ("words", &stats.words)] {
+                println!(
+                    "{}: min {}, 25% {}, median {}, 75% {}, 90% {}, max {}",
+                    label, p.min, p.p25, p.median, p.p75, p.p90, p.max
+                );
+            }
+            println!("points per board:");
+            let most = stats.score_histogram.iter().map(|b| b.count).max();
+            for b in &stats.score_histogram {
+                let bar = b.count * 40 / most.unwrap_or(1).max(1);
+                println!(
+                    "  {:5}-{:<5} {:4} {}",
+                    b.from,
+                    b.to,
+                    b.count,
+                    "#".repeat(bar)
+                );
+            }
+            println!("players:");
+            for p in &stats.players {
+                let recent: Vec<String> = p
+                    .games
+                    .iter()
+                    .rev()
+                    .take(5)
+                    .rev()
+                    .map(|g| g.score.to_string())
+                    .collect();
+                println!(
+                    "  {:10} {:3} games, avg {:.1} points ({:.1}%), last {}: {}",
+                    p.name,
+                    p.games.len(),
+                    p.average_score,
+                    p.average_percent,
+                    recent.len(),
+                    recent.join(" ")
+                );
+            }
+            println!("most often missed:");
+            for m in &stats.missed {
+                println!(
+                    "  {:2} {:13} missed {} of {}",
+                    m.score, m.word, m.missed, m.boards
+                );
+            }
+            println!("most unusual letters:");
+            for l in stats.letters.iter().take(5) {
+                println!(
+                    "  {} seen {} times, expected {:.1} ({:+.1} sd)",
+                    l.letter, l.observed, l.expected, l.z
+                );
+            }
+        }
+    };
+    Ok(())
+}
+
+fn config(opts: options::ConfigOptions) -> Res {
+    match opts.command {
+        options::ConfigCommands::Show => {
+            // Show what can be read, even when a file has an error.
+            let (settings, errors) = Settings::load_readable();
+            settings::show(&settings, &errors);
+            match errors.into_iter().next() {
+                Some(err) => Err(err.into()),
+                None => Ok(()),
+            }
+        }
+    }
+}
+
+fn dict(opts: options::DictOptions, settings: &Settings) -> Res {
+    use options::DictCommands::*;
+    match opts.command {
+        Diff(opts) => dict_diff(opts, settings),
+    }
+}
+
+fn dict_diff(opts: options::DictDiffOptions, settings: &Settings) -> Res {
+    let a = dictionary::open_magic(&Some(opts.a.clone()))?;
+    let b = dictionary::open_magic(&Some(opts.b.clone()))?;
+    if a.alphabet != b.alphabet {
+        return Err(format!(
+            "{} is {} and {} is {}",
+            opts.a,
+            a.alphabet.name(),
+            opts.b,
+            b.alphabet.name()
+        )
+        .into());
+    }
+
+    let counts = diff::diff(&a, &b, |side, word| {
+        if !opts.counts {
+            match side {
+                diff::Side::A => println!("- {}", word),
+                diff::Side::B => println!("+ {}", word),
+            }
+        }
+    });
+    println!("length  only in {}  only in {}", opts.a, opts.b);
+    for (len, (in_a, in_b)) in &counts {
+        println!("{:6}  {:9}  {:9}", len, in_a, in_b);
+    }
+    let (in_a, in_b) = counts
+        .values()
+        .fold((0, 0), |(x, y), (a, b)| (x + a, y + b));
+    println!(" total  {:9}  {:9}", in_a, in_b);
+
+    if let Some(path) = opts.games {
+        // Score every recorded game again with both dictionaries and show the ones that change.
+        let scoring = settings::scoring(settings)?;
+        println!();
+        for (i, game) in games::load(&path)?.iter().enumerate() {
+            let players: Vec<(String, Vec<String>)> = game
+                .players
+                .iter()
+                .map(|p| (p.name.clone(), p.words.clone()))
+                .collect();
+            let in_a = games::Game::new(game.date, &game.board, players.clone(), &a, &scoring)?;
+            let in_b = games::Game::new(game.date, &game.board, players, &b, &scoring)?;
+            if in_a == in_b {
+                continue;
+            }
+            let mut changes = vec![format!(
+                "{} -> {} words, {} -> {} points",
+                in_a.total_words, in_b.total_words, in_a.total_score, in_b.total_score
+            )];
+            for (pa, pb) in in_a.players.iter().zip(&in_b.players) {
+                if pa.score != pb.score {
+                    changes.push(format!("{} {} -> {}", pa.name, pa.score, pb.score));
+                }
+            }
+            println!(
+                "{:4} {}  {}",
+                i + 1,
+                game.date.format("%Y-%m-%d %H:%M"),
+                changes.join(", ")
+            );
+        }
+    }
+    Ok(())
 }
 
 fn compile(opts: options::CompileOptions) -> Res {
-    let (dict, _) = dictionary::open_json(&opts.input)?;
+    let alphabet = match Alphabet::named(&opts.alphabet) {
+        Some(alphabet) => alphabet,
+        None => {
+            return Err(CliError::Usage(format!(
+                "unknown alphabet {:?}, use one of {}",
+                opts.alphabet,
+                alphabet::NAMES.join(", ")
+            ))
+            .into())
+        }
+    };
+    let (dict, defs) = dictionary::open_json(&opts.input, Arc::new(alphabet))?;
+    let defs_output = match &opts.defs_output {
+        Some(path) => path.to_owned(),
+        None => std::path::Path::new(&opts.output)
+            .with_extension("defs")
+            .to_string_lossy()
+            .into_owned(),
+    };
+    let freq = match &opts.frequencies {
+        Some(path) => Some(frequency::Commonness::build(
+            &dict,
+            &std::fs::read_to_string(path)?,
+        )),
+        None => None,
+    };
+    let mut paths = vec![opts.output.clone(), defs_output];
+    if freq.is_some() {
+        let freq_output = std::path::Path::new(&opts.output).with_extension("freq");
+        paths.push(freq_output.to_string_lossy().into_owned());
+    }
+
+    // Open every output before writing any, so that one that's in the way doesn't leave the
+    // others out of sync.
+    let mut outputs = vec![];
+    for path in &paths {
+        match create_output(path, opts.overwrite) {
+            Ok(outf) => outputs.push(outf),
+            Err(err) => {
+                // Without --overwrite, the files opened so far are new and empty.
+                if !opts.overwrite {
+                    for path in &paths[..outputs.len()] {
+                        let _ = std::fs::remove_file(path);
+                    }
+                }
+                return Err(err.into());
+            }
+        }
+    }
+    let mut outputs = outputs.into_iter();
+    dict.save(&mut outputs.next().unwrap())?;
+    defs.save(&mut outputs.next().unwrap())?;
+    if let (Some(freq), Some(mut outf)) = (freq, outputs.next()) {
+        freq.save(&mut outf)?;
+    }
+    Ok(())
+}
+
+fn create_output(path: &str, overwrite: bool) -> Result<BufWriter<std::fs::File>, CliError> {
     let mut fo = OpenOptions::new();
     fo.write(true).truncate(true);
-    if opts.overwrite {
+    if overwrite {
         fo.create(true);
     } else {
         fo.create_new(true);
     }
-    let outf = match fo.open(&opts.output) {
-        Ok(f) => f,
+    match fo.open(path) {
+        Ok(f) => Ok(BufWriter::new(f)),
         Err(err) => match err.kind() {
-            std::io::ErrorKind::AlreadyExists => {
-                return Err(Box::new(GenericError(format!(
-                    "{} already exists, use --overwrite to replace it",
-                    opts.output
-                ))))
-            }
-            _ => return Err(Box::new(GenericError(format!("{}: {}", opts.output, err)))),
+            std::io::ErrorKind::AlreadyExists => Err(CliError::CantCreate(format!(
+                "{} already exists, use --overwrite to replace it",
+                path
+            ))),
+            _ => Err(CliError::CantCreate(format!("{}: {}", path, err))),
         },
-    };
-    let mut outf = BufWriter::new(outf);
-    dict.save(&mut outf)?;
-    Ok(())
+    }
 }
 
-// TODO - use thiserror
+/// Errors from the commands themselves, rather than from the modules they use.
 #[derive(Debug)]
-struct GenericError(String);
+enum CliError {
+    /// The options don't make sense.
+    Usage(String),
+    /// Something that was asked for isn't there.
+    NotFound(String),
+    /// An input file couldn't be used.
+    BadInput(String),
+    /// An output file couldn't be created.
+    CantCreate(String),
+    /// The command ran but couldn't do what was asked.
+    Failed(String),
+}
 
-impl std::error::Error for GenericError {}
+impl std::error::Error for CliError {}
 
-impl std::fmt::Display for GenericError {
+impl std::fmt::Display for CliError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
-        write!(f, "{}", &self.0)
+        match self {
+            CliError::Usage(msg)
+            | CliError::NotFound(msg)
+            | CliError::BadInput(msg)
+            | CliError::CantCreate(msg)
+            | CliError::Failed(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+// Exit codes follow sysexits.h, so that scripts can tell bad input from a missing file.
+const EX_FAILED: i32 = 1;
+const EX_USAGE: i32 = 64;
+const EX_DATAERR: i32 = 65;
+const EX_NOINPUT: i32 = 66;
+const EX_CANTCREAT: i32 = 73;
+const EX_IOERR: i32 = 74;
+const EX_CONFIG: i32 = 78;
+
+fn exit_code(err: &(dyn Error + 'static)) -> i32 {
+    use dictionary::DError;
+
+    if let Some(err) = err.downcast_ref::<CliError>() {
+        return match err {
+            CliError::Usage(_) => EX_USAGE,
+            CliError::NotFound(_) => EX_NOINPUT,
+            CliError::BadInput(_) => EX_DATAERR,
+            CliError::CantCreate(_) => EX_CANTCREAT,
+            CliError::Failed(_) => EX_FAILED,
+        };
+    }
+    if let Some(err) = err.downcast_ref::<DError>() {
+        return match err {
+            DError::NotFound { .. } => EX_NOINPUT,
+            DError::Io(_) => EX_IOERR,
+            DError::Corrupt { .. } | DError::Json(_) => EX_DATAERR,
+            DError::Config(_) => EX_CONFIG,
+        };
+    }
+    if err.is::<ConfigError>() {
+        return EX_CONFIG;
+    }
+    if let Some(ClientError::BadUrl(_)) = err.downcast_ref::<ClientError>() {
+        return EX_USAGE;
+    }
+    if err.is::<wordle::ClueError>() {
+        return EX_USAGE;
+    }
+    if err.is::<wordsearch::WSError>() || err.is::<serde_json::Error>() {
+        return EX_DATAERR;
+    }
+    if let Some(err) = err.downcast_ref::<std::io::Error>() {
+        return match err.kind() {
+            std::io::ErrorKind::NotFound => EX_NOINPUT,
+            _ => EX_IOERR,
+        };
     }
+    EX_FAILED
 }
@@ -0,0 +1,219 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, SystemTime};
+
+// What the web server has been doing, for /metrics. The output is Prometheus's text format.
+
+/// The upper bounds of the histogram buckets, in seconds.
+const BUCKETS: [f64; 12] = [
+    0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5,
+];
+
+#[derive(Default)]
+pub struct Metrics(Mutex<Recorded>);
+
+#[derive(Default)]
+struct Recorded {
+    // Counts by route, method, and status.
+    requests: BTreeMap<(String, String, u16), u64>,
+    // Latencies by route.
+    latencies: BTreeMap<String, Histogram>,
+    solves: Histogram,
+    // How long the last successful load took, and when it finished.
+    load: Option<(Duration, SystemTime)>,
+    load_failures: u64,
+}
+
+#[derive(Clone, Default)]
+struct Histogram {
+    // The number of observations in each bucket, not counting the smaller buckets.
+    counts: [u64; BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, elapsed: Duration) {
+        let secs = elapsed.as_secs_f64();
+        if let Some(i) = BUCKETS.iter().position(|&bound| secs <= bound) {
+            self.counts[i] += 1;
+        }
+        self.count += 1;
+        self.sum += secs;
+    }
+}
+
+impl Metrics {
+    fn recorded(&self) -> std::sync::MutexGuard<'_, Recorded> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// A response to a request for `route`, which is the route's pattern, like
+    /// "/boggle/dict/words/:word", so that every word doesn't get its own series.
+    pub fn request(&self, route: &str, method: &str, status: u16, elapsed: Duration) {
+        let mut recorded = self.recorded();
+        *recorded
+            .requests
+            .entry((route.to_owned(), method.to_owned(), status))
+            .or_default() += 1;
+        recorded
+            .latencies
+            .entry(route.to_owned())
+            .or_default()
+            .observe(elapsed);
+    }
+
+    /// A board that was solved, not answered from the cache.
+    pub fn solve(&self, elapsed: Duration) {
+        self.recorded().solves.observe(elapsed);
+    }
+
+    pub fn loaded(&self, elapsed: Duration) {
+        self.recorded().load = Some((elapsed, SystemTime::now()));
+    }
+
+    pub fn load_failed(&self) {
+        self.recorded().load_failures += 1;
+    }
+
+    pub fn write(&self, text: &mut Text) {
+        let recorded = self.recorded();
+
+        let name = "boggle_ocr_http_requests_total";
+        text.family(name, "counter", "Requests by route, method, and status.");
+        for ((route, method, status), count) in &recorded.requests {
+            let status = status.to_string();
+            let labels = [
+                ("route", route.as_str()),
+                ("method", method.as_str()),
+                ("status", &status),
+            ];
+            text.sample(name, &labels, *count as f64);
+        }
+
+        let name = "boggle_ocr_http_request_duration_seconds";
+        text.family(name, "histogram", "How long requests took, by route.");
+        for (route, histogram) in &recorded.latencies {
+            text.histogram(name, &[("route", route.as_str())], histogram);
+        }
+
+        let name = "boggle_ocr_solve_duration_seconds";
+        text.family(name, "histogram", "How long solving a board took.");
+        text.histogram(name, &[], &recorded.solves);
+
+        if let Some((elapsed, at)) = recorded.load {
+            let name = "boggle_ocr_dictionary_load_duration_seconds";
+            text.family(
+                name,
+                "gauge",
+                "How long the last load of the dictionaries took.",
+            );
+            text.sample(name, &[], elapsed.as_secs_f64());
+            let name = "boggle_ocr_dictionary_load_timestamp_seconds";
+            text.family(name, "gauge", "When the dictionaries were last loaded.");
+            let at = at
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default();
+            text.sample(name, &[], at.as_secs_f64());
+        }
+        let name = "boggle_ocr_dictionary_load_failures_total";
+        text.family(
+            name,
+            "counter",
+            "Loads that failed and kept the old dictionaries.",
+        );
+        text.sample(name, &[], recorded.load_failures as f64);
+    }
+}
+
+/// Metrics in Prometheus's text format.
+#[derive(Default)]
+pub struct Text(String);
+
+impl Text {
+    /// Start a metric. Its samples come next.
+    pub fn family(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.0, "# HELP {} {}", name, help);
+        let _ = writeln!(self.0, "# TYPE {} {}", name, kind);
+    }
+
+    pub fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.0 += name;
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(label, value)| format!("{}=\"{}\"", label, escape(value)))
+                .collect();
+            let _ = write!(self.0, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(self.0, " {}", value);
+    }
+
+    fn histogram(&mut self, name: &str, labels: &[(&str, &str)], histogram: &Histogram) {
+        let bucket = format!("{}_bucket", name);
+        let mut count = 0;
+        for (bound, n) in BUCKETS.iter().zip(histogram.counts) {
+            count += n;
+            let le = bound.to_string();
+            self.sample(
+                &bucket,
+                &[labels, &[("le", le.as_str())]].concat(),
+                count as f64,
+            );
+        }
+        let labels_inf = [labels, &[("le", "+Inf")]].concat();
+        self.sample(&bucket, &labels_inf, histogram.count as f64);
+        self.sample(&format!("{}_sum", name), labels, histogram.sum);
+        self.sample(&format!("{}_count", name), labels, histogram.count as f64);
+    }
+
+    pub fn finish(self) -> String {
+        self.0
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', "\\\"")
+        .replace('\n', r"\n")
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{Metrics, Text};
+
+    #[test]
+    fn write() {
+        let metrics = Metrics::default();
+        metrics.request("/dicts", "GET", 200, Duration::from_millis(3));
+        metrics.request("/dicts", "GET", 200, Duration::from_secs(5));
+        metrics.request("/dicts", "GET", 404, Duration::from_millis(3));
+        let mut text = Text::default();
+        metrics.write(&mut text);
+        text.sample("quoted", &[("dict", "a\"b")], 1.0);
+        let text = text.finish();
+        let lines: Vec<&str> = text.lines().collect();
+
+        for line in [
+            "# TYPE boggle_ocr_http_requests_total counter",
+            r#"boggle_ocr_http_requests_total{route="/dicts",method="GET",status="200"} 2"#,
+            r#"boggle_ocr_http_requests_total{route="/dicts",method="GET",status="404"} 1"#,
+            r#"boggle_ocr_http_request_duration_seconds_bucket{route="/dicts",le="0.001"} 0"#,
+            r#"boggle_ocr_http_request_duration_seconds_bucket{route="/dicts",le="0.005"} 2"#,
+            r#"boggle_ocr_http_request_duration_seconds_bucket{route="/dicts",le="2.5"} 2"#,
+            r#"boggle_ocr_http_request_duration_seconds_bucket{route="/dicts",le="+Inf"} 3"#,
+            r#"boggle_ocr_http_request_duration_seconds_count{route="/dicts"} 3"#,
+            "boggle_ocr_solve_duration_seconds_count 0",
+            "boggle_ocr_dictionary_load_failures_total 0",
+            r#"quoted{dict="a\"b"} 1"#,
+        ] {
+            assert!(lines.contains(&line), "missing {}", line);
+        }
+        // Nothing has been loaded yet.
+        assert!(!text.contains("boggle_ocr_dictionary_load_duration_seconds"));
+    }
+}
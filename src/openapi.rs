@@ -0,0 +1,226 @@
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde_json::{json, Value};
+
+use boggle_ocr::api::{DictInfo, ErrorBody, SolveRequest, SolveResponse};
+use boggle_ocr::stats::Stats;
+
+use crate::cache::CacheStats;
+use crate::webserver::{DictQuery, SolveBoggleRequest, SolveBoggleResponse};
+
+// The OpenAPI description of the web server, served at /api/openapi.json. The schemas come from
+// the request and response types, so they can't drift from what the handlers do.
+
+pub fn document() -> Value {
+    let mut gen = SchemaSettings::openapi3().into_generator();
+    let dict_param = query_params::<DictQuery>(&mut gen);
+
+    let paths = json!({
+        "/api/v1/boggle/solve": {
+            "post": {
+                "operationId": "solve",
+                "summary": "Find the words on a board.",
+                "requestBody": {"required": true, "content": json_content::<SolveRequest>(&mut gen)},
+                "responses": {
+                    "200": response("The solution.", json_content::<SolveResponse>(&mut gen)),
+                    "400": error("The body or the board isn't valid.", &mut gen),
+                    "404": error("There's no dictionary with that name.", &mut gen),
+                },
+            },
+        },
+        "/boggle/solver/solution": {
+            "get": {
+                "operationId": "solveQuery",
+                "summary": "Find the best words on a board.",
+                "parameters": query_params::<SolveBoggleRequest>(&mut gen),
+                "responses": {
+                    "200": response("The best words.", json_content::<SolveBoggleResponse>(&mut gen)),
+                    "304": {"description": "The solution hasn't changed since the one with the ETag in If-None-Match."},
+                    "400": error("The board isn't valid.", &mut gen),
+                    "404": error("There's no dictionary with that name.", &mut gen),
+                },
+            },
+        },
+        "/boggle/dict/words/{word}": {
+            "get": {
+                "operationId": "define",
+                "summary": "Look up a word.",
+                "parameters": [
+                    {"name": "word", "in": "path", "required": true, "schema": {"type": "string"}},
+                    dict_param[0],
+                ],
+                "responses": {
+                    "200": text("The definition, which is empty if the dictionary doesn't have one."),
+                    "404": error("The word isn't in the dictionary.", &mut gen),
+                },
+            },
+        },
+        "/boggle/stats/data": {
+            "get": {
+                "operationId": "stats",
+                "summary": "Statistics for the recorded games.",
+                "parameters": dict_param,
+                "responses": {
+                    "200": response("The statistics.", json_content::<Stats>(&mut gen)),
+                    "404": error("There's no dictionary with that name.", &mut gen),
+                },
+            },
+        },
+        "/dicts": {
+            "get": {
+                "operationId": "dicts",
+                "summary": "List the loaded dictionaries.",
+                "responses": {
+                    "200": response("The dictionaries, default first.", json_content::<Vec<DictInfo>>(&mut gen)),
+                },
+            },
+        },
+        "/cache": {
+            "get": {
+                "operationId": "cache",
+                "summary": "How well the cache of solved boards is working.",
+                "responses": {
+                    "200": response("The cache's size and counts.", json_content::<CacheStats>(&mut gen)),
+                },
+            },
+        },
+        "/admin/reload": {
+            "post": {
+                "operationId": "reload",
+                "summary": "Load the dictionaries again.",
+                "security": [{"bearer": []}],
+                "responses": {
+                    "200": response("The new dictionaries.", json_content::<Vec<DictInfo>>(&mut gen)),
+                    "401": error("The token is wrong or missing.", &mut gen),
+                    "404": error("The server has no admin token.", &mut gen),
+                    "422": error("A dictionary couldn't be loaded. The old ones are still used.", &mut gen),
+                },
+            },
+        },
+        "/healthz": {
+            "get": {
+                "operationId": "healthz",
+                "summary": "Whether the server is up, even if it's still loading.",
+                "responses": {"200": text("ok")},
+            },
+        },
+        "/readyz": {
+            "get": {
+                "operationId": "readyz",
+                "summary": "Whether the dictionaries are loaded, so the server can answer requests.",
+                "responses": {
+                    "200": text("ready"),
+                    "503": error("The dictionaries are still loading.", &mut gen),
+                },
+            },
+        },
+        "/metrics": {
+            "get": {
+                "operationId": "metrics",
+                "summary": "Request counts and latencies, solve times, and dictionary and cache sizes.",
+                "responses": {"200": text("Metrics in the Prometheus text format.")},
+            },
+        },
+        "/api/openapi.json": {
+            "get": {
+                "operationId": "openapi",
+                "summary": "This document.",
+                "responses": {
+                    "200": response("The OpenAPI document.", json!({"application/json": {"schema": {"type": "object"}}})),
+                },
+            },
+        },
+    });
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {"title": "boggle-ocr", "version": env!("CARGO_PKG_VERSION")},
+        "paths": paths,
+        "components": {
+            "schemas": gen.take_definitions(),
+            "securitySchemes": {"bearer": {"type": "http", "scheme": "bearer"}},
+        },
+    })
+}
+
+fn json_content<T: JsonSchema>(gen: &mut SchemaGenerator) -> Value {
+    json!({"application/json": {"schema": gen.subschema_for::<T>()}})
+}
+
+fn response(description: &str, content: Value) -> Value {
+    json!({"description": description, "content": content})
+}
+
+fn text(description: &str) -> Value {
+    response(
+        description,
+        json!({"text/plain": {"schema": {"type": "string"}}}),
+    )
+}
+
+fn error(description: &str, gen: &mut SchemaGenerator) -> Value {
+    response(description, json_content::<ErrorBody>(gen))
+}
+
+// A struct that's read from the query string, as a list of parameters.
+fn query_params<T: JsonSchema>(gen: &mut SchemaGenerator) -> Vec<Value> {
+    let object = match T::json_schema(gen) {
+        Schema::Object(schema) => schema.object.unwrap_or_default(),
+        Schema::Bool(_) => return vec![],
+    };
+    object
+        .properties
+        .into_iter()
+        .map(|(name, schema)| {
+            let mut param = json!({
+                "name": name,
+                "in": "query",
+                "required": object.required.contains(&name),
+                "schema": schema,
+            });
+            // OpenAPI puts the description on the parameter, not its schema.
+            if let Some(description) = param["schema"]
+                .as_object_mut()
+                .and_then(|s| s.remove("description"))
+            {
+                param["description"] = description;
+            }
+            param
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn document() {
+        let doc = super::document();
+        let schemas = doc["components"]["schemas"].as_object().unwrap();
+        for name in [
+            "SolveRequest",
+            "SolveResponse",
+            "SolveBoggleResponse",
+            "ErrorBody",
+        ] {
+            assert!(schemas.contains_key(name), "missing {}", name);
+        }
+
+        // Every reference is to a schema in the document.
+        let text = doc.to_string();
+        for reference in text.split("\"$ref\":\"#/components/schemas/").skip(1) {
+            let name = &reference[..reference.find('"').unwrap()];
+            assert!(schemas.contains_key(name), "dangling reference to {}", name);
+        }
+
+        let params = &doc["paths"]["/boggle/solver/solution"]["get"]["parameters"];
+        let board = params
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|p| p["name"] == "board")
+            .unwrap();
+        assert_eq!(board["required"], true);
+        assert_eq!(board["schema"]["type"], "string");
+    }
+}
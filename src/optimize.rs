@@ -0,0 +1,415 @@
+use std::sync::Mutex;
+
+use clap::ArgEnum;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use super::dice::DiceSet;
+use super::dictionary::{Dictionary, Letter};
+use super::wordsearch::{self, Scoring};
+
+// Simulated annealing over boards. Each thread does a series of restarts from a random board,
+// and the best board seen by any thread is reported through a callback so that the caller can
+// print it or save a checkpoint.
+
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Objective {
+    /// Maximize the total score.
+    Score,
+    /// Maximize the number of words.
+    Words,
+}
+
+pub struct Optimizer<'a> {
+    pub dict: &'a Dictionary,
+    /// How words are counted and scored.
+    pub scoring: &'a Scoring,
+    /// Rows (and columns) on the board. Ignored if `dice` is set.
+    pub size: usize,
+    /// Only make boards that could be rolled with these dice.
+    pub dice: Option<DiceSet>,
+    pub objective: Objective,
+    /// How many changes to try in each restart.
+    pub steps: usize,
+    /// The starting temperature. Higher temperatures accept more bad moves early on.
+    pub temperature: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Found {
+    /// The board, in the same format as the files in testdata.
+    pub board: String,
+    pub words: usize,
+    pub score: u32,
+}
+
+impl Found {
+    fn value(&self, objective: Objective) -> u32 {
+        match objective {
+            Objective::Score => self.score,
+            Objective::Words => self.words as u32,
+        }
+    }
+}
+
+/// Why a search can't resume from a board.
+#[derive(Debug, PartialEq)]
+pub enum StartError {
+    WrongSize {
+        tiles: usize,
+        size: usize,
+    },
+    /// The dice can't roll the board.
+    NotRollable(DiceSet),
+}
+
+impl std::fmt::Display for StartError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StartError::WrongSize { tiles, size } => write!(
+                f,
+                "the board has {} tiles, but a {}x{} board has {}",
+                tiles,
+                size,
+                size,
+                size * size
+            ),
+            StartError::NotRollable(set) => write!(f, "the {:?} dice can't roll the board", set),
+        }
+    }
+}
+
+impl std::error::Error for StartError {}
+
+#[derive(Clone)]
+struct State {
+    cells: Vec<char>,
+    // dice[i] is the index of the die in cell i, if the board is made from dice.
+    dice: Option<Vec<usize>>,
+}
+
+impl<'a> Optimizer<'a> {
+    fn size(&self) -> usize {
+        match self.dice {
+            Some(set) => set.size(),
+            None => self.size,
+        }
+    }
+
+    /// Search with `threads` threads, each doing `restarts` restarts. `start` is an optional board
+    /// to resume from, which fails if it doesn't fit the size or dice. `on_improve` is called every
+    /// time a new best board is found.
+    pub fn run<F: Fn(&Found) + Sync>(
+        &self,
+        threads: usize,
+        restarts: usize,
+        seed: Option<u64>,
+        start: Option<&str>,
+        on_improve: F,
+    ) -> Result<Option<Found>, StartError> {
+        let best: Mutex<Option<Found>> = Mutex::new(None);
+        let start = start.map(|board| self.state_for(board)).transpose()?;
+        if let Some(state) = &start {
+            *best.lock().unwrap() = Some(self.eval(state));
+        }
+        std::thread::scope(|s| {
+            for t in 0..threads {
+                let best = &best;
+                let start = &start;
+                let on_improve = &on_improve;
+                s.spawn(move || {
+                    let mut rng = match seed {
+                        Some(seed) => StdRng::seed_from_u64(seed.wrapping_add(t as u64)),
+                        None => StdRng::from_entropy(),
+                    };
+                    for r in 0..restarts {
+                        let state = match start {
+                            Some(state) if r == 0 => state.clone(),
+                            _ => self.random_state(&mut rng),
+                        };
+                        self.anneal(state, &mut rng, |found| {
+                            let mut best = best.lock().unwrap();
+                            let improved = match &*best {
+                                None => true,
+                                Some(b) => found.value(self.objective) > b.value(self.objective),
+                            };
+                            if improved {
+                                *best = Some(found.clone());
+                                on_improve(found);
+                            }
+                        });
+                    }
+                });
+            }
+        });
+        Ok(best.into_inner().unwrap())
+    }
+
+    /// Whether `board` fits, so that a search can resume from it.
+    pub fn check_start(&self, board: &str) -> Result<(), StartError> {
+        self.state_for(board).map(|_| ())
+    }
+
+    fn anneal<R: Rng, F: FnMut(&Found)>(&self, mut state: State, rng: &mut R, mut on_improve: F) {
+        let mut current = self.eval(&state);
+        let mut best = current.value(self.objective);
+        on_improve(&current);
+        for step in 0..self.steps {
+            let temperature = self.temperature * (1.0 - step as f64 / self.steps as f64);
+            let next_state = self.mutate(&state, rng);
+            let next = self.eval(&next_state);
+            let delta = next.value(self.objective) as f64 - current.value(self.objective) as f64;
+            if delta >= 0.0 || (temperature > 0.0 && rng.gen::<f64>() < (delta / temperature).exp())
+            {
+                state = next_state;
+                current = next;
+                if current.value(self.objective) > best {
+                    best = current.value(self.objective);
+                    on_improve(&current);
+                }
+            }
+        }
+    }
+
+    fn random_state<R: Rng>(&self, rng: &mut R) -> State {
+        match self.dice {
+            Some(set) => {
+                let mut dice: Vec<usize> = (0..set.dice().len()).collect();
+                dice.shuffle(rng);
+                let cells = dice.iter().map(|d| roll_die(set, *d, rng)).collect();
+                State {
+                    cells,
+                    dice: Some(dice),
+                }
+            }
+            None => State {
+                cells: (0..self.size * self.size)
+                    .map(|_| self.random_letter(rng))
+                    .collect(),
+                dice: None,
+            },
+        }
+    }
+
+    fn mutate<R: Rng>(&self, state: &State, rng: &mut R) -> State {
+        let mut res = state.clone();
+        let n = res.cells.len();
+        let i = rng.gen_range(0..n);
+        if rng.gen_bool(0.5) {
+            // Swap two cells.
+            let j = rng.gen_range(0..n);
+            res.cells.swap(i, j);
+            if let Some(dice) = &mut res.dice {
+                dice.swap(i, j);
+            }
+        } else {
+            // Change one letter.
+            res.cells[i] = match (&res.dice, self.dice) {
+                (Some(dice), Some(set)) => roll_die(set, dice[i], rng),
+                _ => self.random_letter(rng),
+            };
+        }
+        res
+    }
+
+    fn eval(&self, state: &State) -> Found {
+        let board: String = state
+            .cells
+            .chunks(self.size())
+            .map(|row| row.iter().collect::<String>() + "\n")
+            .collect();
+        // Dice can have letters that a custom alphabet doesn't, and those boards have no words.
+        let words: Vec<String> = match wordsearch::boggled_in(&board, &self.dict.alphabet) {
+            Ok(parsed) => wordsearch::find_words(self.dict, &parsed)
+                .into_iter()
+                .filter(|w| self.scoring.counts(w))
+                .collect(),
+            Err(_) => vec![],
+        };
+        Found {
+            score: words.iter().map(|w| self.scoring.score(w)).sum(),
+            words: words.len(),
+            board,
+        }
+    }
+
+    // Turn a saved board back into a search state. For dice sets, this needs to find a die for
+    // every letter.
+    fn state_for(&self, board: &str) -> Result<State, StartError> {
+        let alphabet = &self.dict.alphabet;
+        let cells: Vec<char> = board
+            .chars()
+            .filter(|ch| ch.is_alphabetic())
+            .map(|ch| match alphabet.board_letter(ch) {
+                Some(l) => alphabet.board_char(l),
+                None => ch,
+            })
+            .collect();
+        if cells.len() != self.size() * self.size() {
+            return Err(StartError::WrongSize {
+                tiles: cells.len(),
+                size: self.size(),
+            });
+        }
+        let dice = match self.dice {
+            None => None,
+            Some(set) => Some(assign_dice(&cells, set.dice()).ok_or(StartError::NotRollable(set))?),
+        };
+        Ok(State { cells, dice })
+    }
+
+    /// A random tile from the dictionary's alphabet.
+    fn random_letter<R: Rng>(&self, rng: &mut R) -> char {
+        let alphabet = &self.dict.alphabet;
+        alphabet.board_char(Letter::for_i(rng.gen_range(0..alphabet.len())))
+    }
+}
+
+fn roll_die<R: Rng>(set: DiceSet, die: usize, rng: &mut R) -> char {
+    let faces: Vec<char> = set.dice()[die].chars().filter(|ch| *ch != '*').collect();
+    *faces.choose(rng).unwrap()
+}
+
+// Match each cell to a die that has its letter (Kuhn's algorithm).
+fn assign_dice(cells: &[char], dice: &[&str]) -> Option<Vec<usize>> {
+    fn try_cell(
+        cell: usize,
+        cells: &[char],
+        dice: &[&str],
+        seen: &mut [bool],
+        die_for: &mut [Option<usize>],
+    ) -> bool {
+        for (d, faces) in dice.iter().enumerate() {
+            if seen[d] || !faces.contains(cells[cell]) {
+                continue;
+            }
+            seen[d] = true;
+            let free = match die_for[d] {
+                None => true,
+                Some(other) => try_cell(other, cells, dice, seen, die_for),
+            };
+            if free {
+                die_for[d] = Some(cell);
+                return true;
+            }
+        }
+        false
+    }
+
+    let mut die_for = vec![None; dice.len()];
+    for cell in 0..cells.len() {
+        let mut seen = vec![false; dice.len()];
+        if !try_cell(cell, cells, dice, &mut seen, &mut die_for) {
+            return None;
+        }
+    }
+    let mut res = vec![0; cells.len()];
+    for (d, cell) in die_for.into_iter().enumerate() {
+        if let Some(cell) = cell {
+            res[cell] = d;
+        }
+    }
+    Some(res)
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use rand::SeedableRng;
+
+    use super::{assign_dice, Objective, Optimizer, StartError};
+    use crate::alphabet::Alphabet;
+    use crate::dice::DiceSet;
+    use crate::dictionary::{build, build_dictionary};
+    use crate::wordsearch::Scoring;
+
+    #[test]
+    fn assign_dice_finds_a_match() {
+        let dice = ["AB", "AC", "BD"];
+        assert_eq!(assign_dice(&['A', 'C', 'B'], &dice), Some(vec![0, 1, 2]));
+        assert_eq!(assign_dice(&['C', 'A', 'B'], &dice), Some(vec![1, 0, 2]));
+        assert_eq!(assign_dice(&['C', 'C', 'B'], &dice), None);
+    }
+
+    #[test]
+    fn finds_better_boards() {
+        let dict = build_dictionary(vec!["cat", "act", "tac", "cats", "scat"]);
+        let opt = Optimizer {
+            dict: &dict,
+            scoring: &Scoring::default(),
+            size: 4,
+            dice: None,
+            objective: Objective::Words,
+            steps: 2000,
+            temperature: 1.0,
+        };
+        let found = opt.run(2, 2, Some(1), None, |_| ()).unwrap().unwrap();
+        assert_eq!(found.words, 5, "{}", found.board);
+    }
+
+    #[test]
+    fn seeds_wrap_around() {
+        let dict = build_dictionary(vec!["cat"]);
+        let opt = Optimizer {
+            dict: &dict,
+            scoring: &Scoring::default(),
+            size: 4,
+            dice: None,
+            objective: Objective::Score,
+            steps: 10,
+            temperature: 1.0,
+        };
+        assert!(opt.run(2, 1, Some(u64::MAX), None, |_| ()).is_ok());
+    }
+
+    #[test]
+    fn resumes_from_a_dice_board() {
+        let dict = build_dictionary(vec!["cat"]);
+        let opt = Optimizer {
+            dict: &dict,
+            scoring: &Scoring::default(),
+            size: 0,
+            dice: Some(DiceSet::New),
+            objective: Objective::Score,
+            steps: 0,
+            temperature: 1.0,
+        };
+        let board = "ANCH\nOOTW\nTSIY\nZRLE\n";
+        assert_eq!(
+            opt.check_start("ABCD\nEFGH\nIJKL\nMNOP\n"),
+            Err(StartError::NotRollable(DiceSet::New))
+        );
+        assert_eq!(
+            opt.check_start("ABCDE\nFGHIJ\nKLMNO\nPQRST\nUVWXY\n"),
+            Err(StartError::WrongSize { tiles: 25, size: 4 })
+        );
+        let found = opt
+            .run(1, 1, Some(1), Some(board), |_| ())
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.board, board);
+    }
+
+    #[test]
+    fn uses_the_dictionary_alphabet() {
+        let spanish = Arc::new(Alphabet::named("spanish").unwrap());
+        let dict = build(["año", "oso"], spanish.clone());
+        let opt = Optimizer {
+            dict: &dict,
+            scoring: &Scoring::default(),
+            size: 4,
+            dice: None,
+            objective: Objective::Words,
+            steps: 0,
+            temperature: 1.0,
+        };
+        let state = opt.state_for("AÑOX\nOSOX\nXXXX\nXXXX\n").unwrap();
+        assert_eq!(opt.eval(&state).words, 2);
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let letters: String = (0..500).map(|_| opt.random_letter(&mut rng)).collect();
+        assert!(letters.contains('Ñ'));
+    }
+}
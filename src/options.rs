@@ -1,16 +1,25 @@
 use clap::{ArgEnum, Args, Parser, Subcommand};
 
+pub use crate::output::OutputFormat;
+pub use boggle_ocr::dice::DiceSet;
+pub use boggle_ocr::frequency::Tier;
+pub use boggle_ocr::optimize::Objective;
+
 // Best docs: https://github.com/clap-rs/clap/tree/v3.0.14/examples/tutorial_derive
 
-pub fn parse() -> Commands {
-    Cli::parse().command
+pub fn parse() -> Cli {
+    Cli::parse()
 }
 
 #[derive(Parser)]
 #[clap(author, version, about, long_about = None)]
-struct Cli {
+pub struct Cli {
+    /// Say which files are searched for and loaded.
+    #[clap(short, long, global = true)]
+    pub verbose: bool,
+
     #[clap(subcommand)]
-    command: Commands,
+    pub command: Commands,
 }
 
 #[derive(Subcommand)]
@@ -27,17 +36,58 @@ pub enum Commands {
     /// Compile a JSON dictionary.
     Compile(CompileOptions),
 
+    /// Work with compiled dictionaries.
+    Dict(DictOptions),
+
     /// Run a web server.
     Serve(ServerOptions),
+
+    /// Roll a random board using real Boggle dice.
+    Generate(GenerateOptions),
+
+    /// Search for the highest-scoring board.
+    Optimize(OptimizeOptions),
+
+    /// Record and look at past games.
+    Game(GameOptions),
+
+    /// Compare the words you found with all the words on a board.
+    Review(ReviewOptions),
+
+    /// Show statistics for all recorded games.
+    Stats(StatsOptions),
+
+    /// Load the dictionary once and then solve boards and look up words.
+    Interactive(InteractiveOptions),
+
+    /// Play a game against the clock.
+    Play(PlayOptions),
+
+    /// Look at the settings from the config files.
+    Config(ConfigOptions),
 }
 
 #[derive(Args)]
 pub struct BoggleOptions {
-    /// The JSON or compiled dictionary to use. Defaults to cached.dict or DICT.json in the current directory.
+    /// The JSON or compiled dictionary to use. Defaults to cached.dict or DICT.json on the search path.
     #[clap(short, long)]
     pub dict: Option<String>,
 
-    /// Use the given JSON dictionary to look up definitions. Defaults to DICT.json in the current directory.
+    /// A file of house-rule words to allow (+word) or ban (-word) on top of the dictionary.
+    #[clap(long)]
+    pub overlay: Option<String>,
+
+    /// Word frequencies from "compile --frequencies". Defaults to cached.freq on the search
+    /// path, if it's there.
+    #[clap(long)]
+    pub freq: Option<String>,
+
+    /// Only show words that are at least this common.
+    #[clap(arg_enum, long)]
+    pub common: Option<Tier>,
+
+    /// Use the given compiled definitions or JSON dictionary to look up definitions. Defaults to
+    /// cached.defs or DICT.json on the search path.
     #[clap(long)]
     pub defs_dict: Option<String>,
 
@@ -45,21 +95,44 @@ pub struct BoggleOptions {
     #[clap(long)]
     pub defs: bool,
 
-    /// Show all matches, not just the first 20.
+    /// Show all matches, not just the best ones (20, or output.limit in the config files).
     #[clap(long)]
     pub show_all: bool,
 
-    /// The board as a text file, one line per row.
-    pub board: String,
+    /// Machine-readable formats include all words, with their paths. Defaults to output.format
+    /// in the config files, or text.
+    #[clap(arg_enum, short, long)]
+    pub format: Option<OutputFormat>,
+
+    /// The board as comma-separated rows, e.g. "abcde,fghij,klmno,pqrst,uvwxy".
+    #[clap(short, long)]
+    pub letters: Option<String>,
+
+    /// The board as a text file, one line per row, or "-" to read it from stdin.
+    pub board: Option<String>,
 }
 
 #[derive(Args)]
 pub struct WordleOptions {
-    /// The JSON or compiled dictionary to use. Defaults to cached.dict or OWL2.json in the current
-    /// directory.
+    /// The JSON or compiled dictionary to use. Defaults to cached.dict or DICT.json on the
+    /// search path.
     #[clap(short, long)]
     pub dict: Option<String>,
 
+    /// A file of house-rule words to allow (+word) or ban (-word) on top of the dictionary.
+    #[clap(long)]
+    pub overlay: Option<String>,
+
+    /// Word frequencies from "compile --frequencies". Defaults to cached.freq on the search
+    /// path, if it's there.
+    #[clap(long)]
+    pub freq: Option<String>,
+
+    /// Only show words that are at least this common. With a frequency file, the most common words
+    /// come first.
+    #[clap(arg_enum, long)]
+    pub common: Option<Tier>,
+
     /// The letters you know are in the solution.
     #[clap(short, long)]
     pub include: Option<String>,
@@ -68,21 +141,40 @@ pub struct WordleOptions {
     #[clap(short, long)]
     pub exclude: Option<String>,
 
+    /// Defaults to output.format in the config files, or text.
+    #[clap(arg_enum, short, long)]
+    pub format: Option<OutputFormat>,
+
     /// The puzzle with green letters filled in, e.g. "--b--".
     pub pattern: Option<String>,
 }
 
 #[derive(Args)]
 pub struct SummarizeOptions {
-    /// The JSON or compiled dictionary to use. Defaults to cached.dict or OWL2.json in the current directory.
+    /// The JSON or compiled dictionary to use. Defaults to cached.dict or DICT.json on the search path.
+    /// With --server, the name of one of the server's dictionaries.
     #[clap(short, long)]
     pub dict: Option<String>,
 
-    /// The board as a text file, one line per row.
+    /// Solve the boards with a running web server instead, e.g. "http://127.0.0.1:8000".
+    #[clap(long)]
+    pub server: Option<String>,
+
+    /// Text files with one line per row, or "-" to read from stdin. Separate boards in the same
+    /// file with blank lines.
     pub boards: Vec<String>,
 
+    /// A board as comma-separated rows, e.g. "abcde,fghij,klmno,pqrst,uvwxy". Repeat for more
+    /// boards.
+    #[clap(short, long)]
+    pub letters: Vec<String>,
+
     #[clap(arg_enum, short, long, default_value = "none")]
     pub sort: SortOrder,
+
+    /// Defaults to output.format in the config files, or text.
+    #[clap(arg_enum, short, long)]
+    pub format: Option<OutputFormat>,
 }
 
 #[derive(ArgEnum, Clone)]
@@ -97,6 +189,283 @@ pub enum SortOrder {
     Score,
 }
 
+#[derive(Args)]
+pub struct GenerateOptions {
+    /// The JSON or compiled dictionary to use with --min-words or --min-score. Defaults to
+    /// cached.dict or DICT.json on the search path.
+    #[clap(short, long)]
+    pub dict: Option<String>,
+
+    /// Which dice to roll. Defaults to board.dice in the config files, or big.
+    #[clap(arg_enum, long)]
+    pub dice: Option<DiceSet>,
+
+    /// Seed for the random number generator, to get the same board again.
+    #[clap(long)]
+    pub seed: Option<u64>,
+
+    /// Keep rolling until the board has at least this many words.
+    #[clap(long, default_value = "0")]
+    pub min_words: usize,
+
+    /// Keep rolling until the board is worth at least this many points.
+    #[clap(long, default_value = "0")]
+    pub min_score: u32,
+
+    /// Give up after rolling this many boards.
+    #[clap(long, default_value = "10000")]
+    pub max_tries: usize,
+}
+
+#[derive(Args)]
+pub struct OptimizeOptions {
+    /// The JSON or compiled dictionary to use. Defaults to cached.dict or DICT.json on the
+    /// search path.
+    #[clap(short, long)]
+    pub dict: Option<String>,
+
+    /// The number of rows and columns on the board. Defaults to board.size in the config files,
+    /// or 5.
+    #[clap(long)]
+    pub size: Option<usize>,
+
+    /// Only consider boards that can be rolled with these dice. Overrides --size, as does
+    /// board.dice in the config files when --size isn't given.
+    #[clap(arg_enum, long)]
+    pub dice: Option<DiceSet>,
+
+    /// What to maximize.
+    #[clap(arg_enum, long, default_value = "score")]
+    pub objective: Objective,
+
+    /// How many changes to try before restarting.
+    #[clap(long, default_value = "20000")]
+    pub steps: usize,
+
+    /// How many times each thread starts over from a random board.
+    #[clap(long, default_value = "4")]
+    pub restarts: usize,
+
+    /// The starting temperature for simulated annealing. Use 0 for plain hill climbing.
+    #[clap(long, default_value = "10")]
+    pub temperature: f64,
+
+    /// How many threads to use. Defaults to the number of cores.
+    #[clap(long)]
+    pub threads: Option<usize>,
+
+    /// Seed for the random number generator.
+    #[clap(long)]
+    pub seed: Option<u64>,
+
+    /// Write the best board so far to this file, and start from it if it already exists.
+    #[clap(long)]
+    pub checkpoint: Option<String>,
+
+    /// Start over and replace the checkpoint if it doesn't fit --size or --dice. Without this,
+    /// that's an error.
+    #[clap(long)]
+    pub overwrite_checkpoint: bool,
+}
+
+#[derive(Args)]
+pub struct GameOptions {
+    /// The game log (default games.jsonl).
+    #[clap(long)]
+    pub games: Option<String>,
+
+    #[clap(subcommand)]
+    pub command: GameCommands,
+}
+
+#[derive(Subcommand)]
+pub enum GameCommands {
+    /// Record a game.
+    Add(GameAddOptions),
+
+    /// List all games.
+    List,
+
+    /// Show one game.
+    Show(GameShowOptions),
+
+    /// Record games from board files, e.g. the ones in boards/.
+    Import(GameImportOptions),
+}
+
+#[derive(Args)]
+pub struct GameAddOptions {
+    /// The JSON or compiled dictionary to use. Defaults to cached.dict or DICT.json on the
+    /// search path.
+    #[clap(short, long)]
+    pub dict: Option<String>,
+
+    /// A player and the words they found, e.g. "alice=cat,dog" or "alice=@alice.txt". Repeat for
+    /// each player.
+    #[clap(short, long)]
+    pub player: Vec<String>,
+
+    /// When the game was played, e.g. "2024-01-31T19:30:00-05:00". Defaults to now.
+    #[clap(long)]
+    pub date: Option<String>,
+
+    /// The board as comma-separated rows, e.g. "abcde,fghij,klmno,pqrst,uvwxy".
+    #[clap(short, long)]
+    pub letters: Option<String>,
+
+    /// The board as a text file, one line per row, or "-" to read it from stdin.
+    pub board: Option<String>,
+}
+
+#[derive(Args)]
+pub struct GameShowOptions {
+    /// The game number, as shown by "game list".
+    pub id: usize,
+}
+
+#[derive(Args)]
+pub struct GameImportOptions {
+    /// The JSON or compiled dictionary to use. Defaults to cached.dict or DICT.json on the
+    /// search path.
+    #[clap(short, long)]
+    pub dict: Option<String>,
+
+    /// Board files, named like the ones add-game creates (2022-01-31-19-30-00.txt).
+    pub boards: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct ReviewOptions {
+    /// The JSON or compiled dictionary to use. Defaults to cached.dict or DICT.json on the
+    /// search path.
+    #[clap(short, long)]
+    pub dict: Option<String>,
+
+    /// Use the given compiled definitions or JSON dictionary to look up definitions. Defaults to
+    /// cached.defs or DICT.json on the search path.
+    #[clap(long)]
+    pub defs_dict: Option<String>,
+
+    /// Show definitions for the missed words.
+    #[clap(long)]
+    pub defs: bool,
+
+    /// How many of the best missed words to show.
+    #[clap(long, default_value = "20")]
+    pub missed: usize,
+
+    /// Word frequencies from "compile --frequencies". Defaults to cached.freq on the search
+    /// path, if it's there.
+    #[clap(long)]
+    pub freq: Option<String>,
+
+    /// Only show missed words that are at least this common.
+    #[clap(arg_enum, long)]
+    pub common: Option<Tier>,
+
+    /// A file with the words you found, separated by spaces, commas, or newlines.
+    #[clap(short, long)]
+    pub words_file: Option<String>,
+
+    /// The board as a text file, one line per row, or "-" to read it from stdin.
+    pub board: String,
+
+    /// The words you found.
+    pub words: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct StatsOptions {
+    /// The JSON or compiled dictionary to use. Defaults to cached.dict or DICT.json on the
+    /// search path.
+    #[clap(short, long)]
+    pub dict: Option<String>,
+
+    /// The game log (default games.jsonl).
+    #[clap(long)]
+    pub games: Option<String>,
+
+    /// How many of the most often missed words to show.
+    #[clap(long, default_value = "20")]
+    pub missed: usize,
+
+    /// The dice the games were rolled with, to compare the letters with. Boards of another size
+    /// are compared with the usual dice for their size. Defaults to board.dice in the config
+    /// files, or big.
+    #[clap(arg_enum, long)]
+    pub dice: Option<DiceSet>,
+
+    /// Defaults to output.format in the config files, or text.
+    #[clap(arg_enum, short, long)]
+    pub format: Option<OutputFormat>,
+
+    /// Which table to write with --format csv or tsv.
+    #[clap(arg_enum, long, default_value = "players")]
+    pub table: StatsTable,
+}
+
+#[derive(ArgEnum, Clone)]
+pub enum StatsTable {
+    /// One row for each player in each game.
+    Players,
+    /// The score histogram.
+    Histogram,
+    /// The most often missed words.
+    Missed,
+    /// Letter frequencies.
+    Letters,
+}
+
+#[derive(Args)]
+pub struct InteractiveOptions {
+    /// The JSON or compiled dictionary to use. Defaults to cached.dict or DICT.json on the
+    /// search path.
+    #[clap(short, long)]
+    pub dict: Option<String>,
+
+    /// Use the given compiled definitions or JSON dictionary to look up definitions. Defaults to
+    /// cached.defs or DICT.json on the search path.
+    #[clap(long)]
+    pub defs_dict: Option<String>,
+}
+
+#[derive(Args)]
+pub struct PlayOptions {
+    /// The JSON or compiled dictionary to use. Defaults to cached.dict or DICT.json on the
+    /// search path.
+    #[clap(short, long)]
+    pub dict: Option<String>,
+
+    /// Use the given compiled definitions or JSON dictionary to look up definitions. Defaults to
+    /// cached.defs or DICT.json on the search path.
+    #[clap(long)]
+    pub defs_dict: Option<String>,
+
+    /// Which dice to roll, if no board is given. Defaults to board.dice in the config files, or
+    /// big.
+    #[clap(arg_enum, long)]
+    pub dice: Option<DiceSet>,
+
+    /// Seed for the random number generator, to play the same board again.
+    #[clap(long)]
+    pub seed: Option<u64>,
+
+    /// How many seconds the game lasts.
+    #[clap(short, long, default_value = "180")]
+    pub time: u64,
+
+    /// How many of the best missed words to show at the end.
+    #[clap(long, default_value = "10")]
+    pub missed: usize,
+
+    /// Play these comma-separated rows instead of rolling a new board.
+    #[clap(short, long)]
+    pub letters: Option<String>,
+
+    /// Play this board file instead of rolling a new one.
+    pub board: Option<String>,
+}
+
 #[derive(Args)]
 pub struct CompileOptions {
     #[clap(short = 'f', long)]
@@ -107,23 +476,109 @@ pub struct CompileOptions {
 
     /// The compiled output file.
     pub output: String,
+
+    /// The language of the words: english, spanish, french, german, or dutch.
+    #[clap(long, default_value = "english")]
+    pub alphabet: String,
+
+    /// A word frequency list with one word per line, optionally followed by a count. Without
+    /// counts, the most common words come first. Writes the output file with a .freq extension.
+    #[clap(long)]
+    pub frequencies: Option<String>,
+
+    /// Where to write the compiled definitions. Defaults to the output file with a .defs
+    /// extension.
+    #[clap(long)]
+    pub defs_output: Option<String>,
+}
+
+#[derive(Args)]
+pub struct DictOptions {
+    #[clap(subcommand)]
+    pub command: DictCommands,
+}
+
+#[derive(Subcommand)]
+pub enum DictCommands {
+    /// Show the words that are only in one of two dictionaries.
+    Diff(DictDiffOptions),
+}
+
+#[derive(Args)]
+pub struct DictDiffOptions {
+    /// Only show the counts, not the words.
+    #[clap(long)]
+    pub counts: bool,
+
+    /// Score the games in this game log with both dictionaries and show the ones that change.
+    #[clap(long)]
+    pub games: Option<String>,
+
+    /// The old dictionary, JSON or compiled. Words only in this one are shown with "-".
+    pub a: String,
+
+    /// The new dictionary, JSON or compiled. Words only in this one are shown with "+".
+    pub b: String,
 }
 
 #[derive(Args)]
 pub struct ServerOptions {
-    /// The address (default 127.0.0.1:0) where the server will listen.
+    /// The address where the server will listen. Defaults to server.addr in the config files, or
+    /// 127.0.0.1:0.
     #[clap(long)]
     pub addr: Option<String>,
 
-    /// The directory where static assets are found.
+    /// The directory where static assets are found. Defaults to server.assets in the config
+    /// files, or assets.
     #[clap(long)]
     pub assets: Option<String>,
 
-    /// The cached.dict file (default cached.dict).
+    /// The cached.dict file (default cached.dict on the search path).
     #[clap(long)]
     pub dict: Option<String>,
 
-    /// The full dictionary, including definitions (default DICT.json).
+    /// The compiled definitions or the full JSON dictionary (default cached.defs or DICT.json
+    /// on the search path).
     #[clap(long)]
     pub defs: Option<String>,
+
+    /// A file of house-rule words to allow (+word) or ban (-word) on top of the dictionary.
+    #[clap(long)]
+    pub overlay: Option<String>,
+
+    /// A JSON file listing several named dictionaries to load instead of --dict and --defs, e.g.
+    /// [{"name": "twl", "dict": "twl.dict", "defs": "TWL.json", "overlay": "house.txt"}]. The
+    /// first one is the default.
+    #[clap(long)]
+    pub dicts: Option<String>,
+
+    /// Check the dictionary files every this many seconds and reload them when they change.
+    /// The server also reloads them on SIGHUP.
+    #[clap(long)]
+    pub watch: Option<u64>,
+
+    /// Enable POST /admin/reload, which needs "Authorization: Bearer <token>".
+    #[clap(long)]
+    pub admin_token: Option<String>,
+
+    /// The game log to show statistics for (default games.jsonl).
+    #[clap(long)]
+    pub games: Option<String>,
+
+    /// How much memory to use for remembering solved boards, in megabytes. 0 turns it off.
+    /// Defaults to server.cache_mb in the config files, or 64.
+    #[clap(long)]
+    pub cache_mb: Option<usize>,
+}
+
+#[derive(Args)]
+pub struct ConfigOptions {
+    #[clap(subcommand)]
+    pub command: ConfigCommands,
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Print every setting, where its value comes from, and which config files were read.
+    Show,
 }
@@ -0,0 +1,64 @@
+use clap::ArgEnum;
+
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human readable text.
+    Text,
+    /// JSON.
+    Json,
+    /// Comma separated values, with a header row.
+    Csv,
+    /// Tab separated values, with a header row.
+    Tsv,
+}
+
+/// Format one row of a CSV or TSV table.
+pub fn table_row<S: AsRef<str>>(format: OutputFormat, fields: &[S]) -> String {
+    match format {
+        OutputFormat::Tsv => tsv_row(fields),
+        _ => csv_row(fields),
+    }
+}
+
+/// Format one TSV row. Tabs and newlines in fields are replaced with spaces.
+pub fn tsv_row<S: AsRef<str>>(fields: &[S]) -> String {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|f| f.as_ref().replace(['\t', '\n', '\r'], " "))
+        .collect();
+    fields.join("\t")
+}
+
+/// Format one CSV row. Fields with commas, quotes, or newlines are quoted.
+pub fn csv_row<S: AsRef<str>>(fields: &[S]) -> String {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|f| {
+            let f = f.as_ref();
+            if f.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", f.replace('"', "\"\""))
+            } else {
+                f.to_owned()
+            }
+        })
+        .collect();
+    fields.join(",")
+}
+
+#[cfg(test)]
+mod test {
+    use super::{csv_row, tsv_row};
+
+    #[test]
+    fn csv_quoting() {
+        assert_eq!(
+            csv_row(&["a", "b c", "d,e", "say \"hi\""]),
+            "a,b c,\"d,e\",\"say \"\"hi\"\"\""
+        );
+    }
+
+    #[test]
+    fn tsv_tabs() {
+        assert_eq!(tsv_row(&["a", "b\tc", "d,e"]), "a\tb c\td,e");
+    }
+}
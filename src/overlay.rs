@@ -0,0 +1,151 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::Path;
+use std::sync::Arc;
+
+use super::alphabet::Alphabet;
+use super::dictionary::{self, Dictionary};
+
+// House rules on top of a compiled dictionary. An overlay file has one word per line, with "+" to
+// allow it or "-" to ban it, and an optional note after the word:
+//
+//     # house rules
+//     +zaxby   the chicken place on main street
+//     -qi      nobody likes it
+//
+// The note is shown as the word's definition. Words are spelled with the dictionary's alphabet, so
+// in French "+café" allows "cafe".
+
+pub struct Overlay {
+    /// Words to allow, with their notes.
+    pub added: BTreeMap<String, Option<String>>,
+    /// Words to ban, with their notes.
+    pub removed: BTreeMap<String, Option<String>>,
+    /// The added words, so that they can be searched like the rest of the dictionary.
+    pub dict: Dictionary,
+}
+
+impl Overlay {
+    /// The note for an added or removed word.
+    pub fn note(&self, word: &str) -> Option<&str> {
+        self.added
+            .get(word)
+            .or_else(|| self.removed.get(word))
+            .and_then(|note| note.as_deref())
+    }
+}
+
+/// Add the overlay at `path`, if there is one, to a dictionary.
+pub fn apply(dict: Dictionary, path: &Option<String>) -> Result<Dictionary, Box<dyn Error>> {
+    match path {
+        Some(path) => {
+            let overlay = open(path, &dict.alphabet)?;
+            Ok(dict.with_overlay(overlay))
+        }
+        None => Ok(dict),
+    }
+}
+
+pub fn open(path: impl AsRef<Path>, alphabet: &Alphabet) -> Result<Overlay, Box<dyn Error>> {
+    parse(&std::fs::read_to_string(path)?, alphabet)
+}
+
+pub fn parse(text: &str, alphabet: &Alphabet) -> Result<Overlay, Box<dyn Error>> {
+    let mut added = BTreeMap::new();
+    let mut removed = BTreeMap::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (sign, rest) = match line.chars().next() {
+            Some(c @ ('+' | '-')) => (c, &line[c.len_utf8()..]),
+            _ => return Err(format!("line {}: start with + or -", i + 1).into()),
+        };
+        let (word, note) = match rest.trim_start().split_once(char::is_whitespace) {
+            Some((word, note)) => (word, Some(note.trim().to_owned())),
+            None => (rest.trim_start(), None),
+        };
+        let word = match alphabet.letters(word) {
+            Some(letters) if !letters.is_empty() => letters
+                .iter()
+                .map(|l| alphabet.text(*l))
+                .collect::<String>(),
+            _ => return Err(format!("line {}: {:?} is not a word", i + 1, word).into()),
+        };
+        match sign {
+            '+' => added.insert(word, note),
+            _ => removed.insert(word, note),
+        };
+    }
+    let dict = dictionary::build(added.keys().map(|w| w.as_str()), Arc::new(alphabet.clone()));
+    Ok(Overlay {
+        added,
+        removed,
+        dict,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::parse;
+    use crate::alphabet::Alphabet;
+    use crate::dictionary::{build, build_dictionary};
+    use crate::wordsearch::{boggled, find_words};
+
+    #[test]
+    fn overlay() {
+        let english = Alphabet::english();
+        let overlay = parse(
+            "# house rules\n+Zax  a tool\n\n-cat bad luck\n-cats\n",
+            &english,
+        )
+        .unwrap();
+        assert_eq!(overlay.note("zax"), Some("a tool"));
+        assert_eq!(overlay.note("cat"), Some("bad luck"));
+        assert_eq!(overlay.note("cats"), None);
+
+        let dict = build_dictionary(vec!["act", "cat", "cats"]).with_overlay(overlay);
+        assert!(dict.contains("zax"));
+        assert!(dict.contains("act"));
+        assert!(!dict.contains("cat"));
+        assert!(!dict.contains("Cat"));
+        assert!(dict.contains("ZAX"));
+        assert_eq!(dict.note("CAT"), Some("bad luck"));
+        assert_eq!(dict.word_count(), 2);
+
+        let board = boggled("acts\nzaxx\nxxxx\nxxxx\n").unwrap();
+        assert_eq!(find_words(&dict, &board), vec!["act", "zax"]);
+    }
+
+    #[test]
+    fn bad_lines() {
+        let english = Alphabet::english();
+        assert!(parse("cat\n", &english).is_err());
+        assert!(parse("+c4t\n", &english).is_err());
+        assert!(parse("+\n", &english).is_err());
+        assert!(parse("+café\n", &english).is_err());
+        // A line that starts with a multi-byte character.
+        assert!(parse("\u{feff}+zzyzx house word\n", &english).is_err());
+        assert!(parse("écrit\n", &english).is_err());
+        assert_eq!(
+            parse("+cat\n×dog\n", &english).err().map(|e| e.to_string()),
+            Some("line 2: start with + or -".to_owned())
+        );
+
+        let french = Alphabet::named("french").unwrap();
+        assert_eq!(parse("+Café\n", &french).unwrap().note("cafe"), None);
+        let banned = parse("-cafe  too french\n", &french).unwrap();
+        let dict = build(["café", "thé"], Arc::new(french.clone())).with_overlay(banned);
+        assert!(!dict.contains("café"));
+        assert!(!dict.contains("Café"));
+        assert!(dict.contains("Thé"));
+        assert_eq!(dict.note("CAFÉ"), Some("too french"));
+        assert!(parse("+Café\n", &french)
+            .unwrap()
+            .added
+            .contains_key("cafe"));
+    }
+}
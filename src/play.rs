@@ -0,0 +1,194 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::io::BufRead;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use boggle_ocr::config::Settings;
+use boggle_ocr::dictionary::{self, Definitions};
+use boggle_ocr::review::{self, Invalid};
+use boggle_ocr::wordsearch::{self, Board, Scoring};
+use boggle_ocr::{boards, dice};
+
+use super::options::PlayOptions;
+use super::settings;
+
+// A solo game against the clock. Words are read from stdin on another thread so that the clock
+// can run out while the player is still typing.
+
+#[derive(Debug, PartialEq)]
+pub enum Feedback {
+    Valid(u32),
+    Duplicate,
+    Invalid(Invalid),
+}
+
+pub struct Round {
+    board: Board,
+    scoring: Scoring,
+    on_board: HashSet<String>,
+    found: Vec<String>,
+    score: u32,
+}
+
+impl Round {
+    pub fn new(dict: &dictionary::Dictionary, board: Board, scoring: Scoring) -> Self {
+        let on_board = wordsearch::find_words(dict, &board)
+            .into_iter()
+            .filter(|w| scoring.counts(w))
+            .collect();
+        Self {
+            board,
+            scoring,
+            on_board,
+            found: vec![],
+            score: 0,
+        }
+    }
+
+    pub fn guess(&mut self, dict: &dictionary::Dictionary, word: &str) -> Feedback {
+        let word = word.trim().to_lowercase();
+        if self.found.contains(&word) {
+            return Feedback::Duplicate;
+        }
+        match review::check(dict, &self.board, &self.scoring, &self.on_board, &word) {
+            Ok(score) => {
+                self.found.push(word);
+                self.score += score;
+                Feedback::Valid(score)
+            }
+            Err(reason) => Feedback::Invalid(reason),
+        }
+    }
+}
+
+pub fn run(opts: PlayOptions, settings: &Settings) -> Result<(), Box<dyn Error>> {
+    let dice = settings::or_setting(opts.dice, settings, "board.dice")?;
+    let scoring = settings::scoring(settings)?;
+    let dict = dictionary::open_magic(&opts.dict)?;
+    let defs = match dictionary::open_defs(&opts.defs_dict) {
+        Ok(defs) => defs,
+        Err(_) if opts.defs_dict.is_none() => Definitions::new(),
+        Err(err) => return Err(err),
+    };
+    let raw_board = match (&opts.board, &opts.letters) {
+        (None, None) => {
+            let mut rng = match opts.seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_entropy(),
+            };
+            dice::roll(dice, &mut rng)
+        }
+        // The words come from stdin, so the board can't.
+        (Some(path), None) if path == "-" => return Err("can't read the board from stdin".into()),
+        (path, letters) => boards::read_board(path.as_deref(), letters.as_deref())?,
+    };
+    let board = wordsearch::boggled_in(&raw_board, &dict.alphabet)?;
+    let mut round = Round::new(&dict, board, scoring);
+
+    println!();
+    for row in round.board.rows_in(&dict.alphabet) {
+        let cells: Vec<String> = row
+            .chars()
+            .map(|ch| match ch {
+                'Q' => "Qu".to_owned(),
+                ch => ch.to_string(),
+            })
+            .collect();
+        println!(
+            "  {}",
+            cells.iter().map(|c| format!("{c:3}")).collect::<String>()
+        );
+    }
+    println!();
+    println!("You have {} seconds. Type one word per line.", opts.time);
+
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            match line {
+                Ok(line) => {
+                    if tx.send(line).is_err() {
+                        break;
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+    });
+
+    let deadline = Instant::now() + Duration::from_secs(opts.time);
+    loop {
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            break;
+        }
+        let line = match rx.recv_timeout(left) {
+            Ok(line) => line,
+            Err(mpsc::RecvTimeoutError::Timeout) => break,
+            // stdin closed, so there's nothing to wait for.
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        };
+        for word in line.split_whitespace() {
+            let msg = match round.guess(&dict, word) {
+                Feedback::Valid(score) => format!("+{score}, {} points", round.score),
+                Feedback::Duplicate => "already found".to_owned(),
+                Feedback::Invalid(reason) => reason.to_string(),
+            };
+            let left = deadline.saturating_duration_since(Instant::now());
+            println!("  {word}: {msg} ({}s left)", left.as_secs());
+        }
+    }
+
+    println!();
+    println!("Time's up!");
+    let r = review::review(&dict, &round.board, &round.scoring, &round.found);
+    println!(
+        "You found {} of {} words, {} of {} points ({:.1}%).",
+        r.found.len(),
+        r.total_words,
+        r.found_score,
+        r.total_score,
+        r.percent()
+    );
+    println!("best words you missed:");
+    for (w, s) in r.missed.iter().take(opts.missed) {
+        let def = defs.get(w).unwrap_or("");
+        println!("  {s:2} {w:13} {def}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Feedback, Round};
+    use boggle_ocr::dictionary::build_dictionary;
+    use boggle_ocr::review::Invalid;
+    use boggle_ocr::wordsearch::{boggled, Scoring};
+
+    #[test]
+    fn feedback() {
+        let dict = build_dictionary(vec!["cat", "cats", "dog"]);
+        let board = boggled("cats\nxxxx\nxxxx\nxxxx\n").unwrap();
+        let mut round = Round::new(&dict, board, Scoring::default());
+        assert_eq!(round.guess(&dict, "cat"), Feedback::Valid(1));
+        assert_eq!(round.guess(&dict, " CAT"), Feedback::Duplicate);
+        assert_eq!(
+            round.guess(&dict, "dog"),
+            Feedback::Invalid(Invalid::NotOnBoard)
+        );
+        assert_eq!(
+            round.guess(&dict, "tac"),
+            Feedback::Invalid(Invalid::NotAWord)
+        );
+        assert_eq!(
+            round.guess(&dict, "at"),
+            Feedback::Invalid(Invalid::TooShort)
+        );
+        assert_eq!(round.guess(&dict, "cats"), Feedback::Valid(1));
+        assert_eq!(round.score, 2);
+    }
+}
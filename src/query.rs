@@ -0,0 +1,170 @@
+use super::alphabet::Alphabet;
+use super::dictionary::{Dictionary, Letter, Node};
+
+// Word searches that aren't tied to a board.
+
+enum Token {
+    One,
+    Any,
+    Tile(Letter),
+}
+
+/// Find words that match a pattern. '?' matches any one letter and '*' matches any number of
+/// letters. Other characters match themselves.
+pub fn pattern(dict: &Dictionary, pattern: &str) -> Vec<String> {
+    let pattern = match tokens(&dict.alphabet, pattern) {
+        Some(pattern) => pattern,
+        None => return vec![],
+    };
+    let mut res = vec![];
+    match_pattern(dict, &dict.root, &pattern, &mut String::new(), &mut res);
+    res.sort();
+    res.dedup();
+    res
+}
+
+// Split a pattern into tiles and wildcards. Returns None if it has a character that isn't in the
+// alphabet.
+fn tokens(alphabet: &Alphabet, pattern: &str) -> Option<Vec<Token>> {
+    let mut res = vec![];
+    let mut literal = String::new();
+    for ch in pattern.chars() {
+        let token = match ch {
+            '?' => Token::One,
+            '*' => Token::Any,
+            ch => {
+                literal.push(ch);
+                continue;
+            }
+        };
+        res.extend(alphabet.letters(&literal)?.into_iter().map(Token::Tile));
+        literal.clear();
+        res.push(token);
+    }
+    res.extend(alphabet.letters(&literal)?.into_iter().map(Token::Tile));
+    Some(res)
+}
+
+fn match_pattern(
+    dict: &Dictionary,
+    node: &Node,
+    pattern: &[Token],
+    scratch: &mut String,
+    res: &mut Vec<String>,
+) {
+    match pattern.first() {
+        None => {
+            if node.terminal {
+                res.push(scratch.clone());
+            }
+        }
+        Some(Token::Any) => {
+            match_pattern(dict, node, &pattern[1..], scratch, res);
+            for (l, child) in node.children() {
+                follow(dict, l, child, pattern, scratch, res);
+            }
+        }
+        Some(Token::One) => {
+            for (l, child) in node.children() {
+                follow(dict, l, child, &pattern[1..], scratch, res);
+            }
+        }
+        Some(Token::Tile(l)) => {
+            if let Some(child) = node.lookup(*l) {
+                follow(dict, *l, child, &pattern[1..], scratch, res);
+            }
+        }
+    }
+}
+
+// Spell the next letter and keep matching.
+fn follow(
+    dict: &Dictionary,
+    l: Letter,
+    child: &Node,
+    pattern: &[Token],
+    scratch: &mut String,
+    res: &mut Vec<String>,
+) {
+    let len = scratch.len();
+    scratch.push_str(dict.alphabet.text(l));
+    match_pattern(dict, child, pattern, scratch, res);
+    scratch.truncate(len);
+}
+
+/// Find words that use all of the given letters. '?' is a blank that can be any letter.
+pub fn anagrams(dict: &Dictionary, letters: &str) -> Vec<String> {
+    let mut counts = vec![0usize; dict.alphabet.len()];
+    let blanks = letters.matches('?').count();
+    // Characters that aren't in the alphabet are ignored.
+    let known: String = letters
+        .chars()
+        .filter(|ch| dict.alphabet.letters(&ch.to_string()).is_some())
+        .collect();
+    for l in dict.alphabet.letters(&known).unwrap_or_default() {
+        counts[l.i()] += 1;
+    }
+    let mut res = vec![];
+    match_anagram(
+        dict,
+        &dict.root,
+        &mut counts,
+        blanks,
+        &mut String::new(),
+        &mut res,
+    );
+    res.sort();
+    res.dedup();
+    res
+}
+
+fn match_anagram(
+    dict: &Dictionary,
+    node: &Node,
+    counts: &mut [usize],
+    blanks: usize,
+    scratch: &mut String,
+    res: &mut Vec<String>,
+) {
+    if blanks == 0 && counts.iter().all(|c| *c == 0) {
+        if node.terminal {
+            res.push(scratch.clone());
+        }
+        return;
+    }
+    for (l, child) in node.children() {
+        let len = scratch.len();
+        scratch.push_str(dict.alphabet.text(l));
+        if counts[l.i()] > 0 {
+            counts[l.i()] -= 1;
+            match_anagram(dict, child, counts, blanks, scratch, res);
+            counts[l.i()] += 1;
+        } else if blanks > 0 {
+            match_anagram(dict, child, counts, blanks - 1, scratch, res);
+        }
+        scratch.truncate(len);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{anagrams, pattern};
+    use crate::dictionary::build_dictionary;
+
+    #[test]
+    fn patterns() {
+        let dict = build_dictionary(vec!["cat", "cats", "cot", "act", "scat"]);
+        assert_eq!(pattern(&dict, "c?t"), vec!["cat", "cot"]);
+        assert_eq!(pattern(&dict, "*at"), vec!["cat", "scat"]);
+        assert_eq!(pattern(&dict, "C*"), vec!["cat", "cats", "cot"]);
+        assert_eq!(pattern(&dict, "c1t"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn anagram_letters() {
+        let dict = build_dictionary(vec!["cat", "cats", "cot", "act", "scat"]);
+        assert_eq!(anagrams(&dict, "tca"), vec!["act", "cat"]);
+        assert_eq!(anagrams(&dict, "ct?"), vec!["act", "cat", "cot"]);
+        assert_eq!(anagrams(&dict, "tacs"), vec!["cats", "scat"]);
+    }
+}
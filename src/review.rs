@@ -0,0 +1,182 @@
+use std::collections::{BTreeMap, HashSet};
+
+use super::dictionary::Dictionary;
+use super::wordsearch::{self, Board, Scoring};
+
+// Compare the words a player found with everything the solver can find.
+
+pub struct Review {
+    /// Valid words the player found, with their scores.
+    pub found: Vec<(String, u32)>,
+    /// Words the player wrote down that don't count.
+    pub invalid: Vec<(String, Invalid)>,
+    /// Words the player didn't find, best first.
+    pub missed: Vec<(String, u32)>,
+    pub total_words: usize,
+    pub total_score: u32,
+    pub found_score: u32,
+    /// For each word length, how many words the player found and how many are on the board.
+    pub by_length: BTreeMap<usize, (usize, usize)>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Invalid {
+    TooShort,
+    NotAWord,
+    NotOnBoard,
+}
+
+impl std::fmt::Display for Invalid {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Invalid::TooShort => write!(fmt, "too short"),
+            Invalid::NotAWord => write!(fmt, "not in the dictionary"),
+            Invalid::NotOnBoard => write!(fmt, "not on the board"),
+        }
+    }
+}
+
+impl Review {
+    /// The percentage of the board's points that the player found.
+    pub fn percent(&self) -> f64 {
+        match self.total_score {
+            0 => 0.0,
+            total => 100.0 * self.found_score as f64 / total as f64,
+        }
+    }
+}
+
+/// Check one word. `on_board` is all of the words on the board that count with `scoring`. Returns
+/// the word's score if it counts.
+pub fn check(
+    dict: &Dictionary,
+    board: &Board,
+    scoring: &Scoring,
+    on_board: &HashSet<String>,
+    word: &str,
+) -> Result<u32, Invalid> {
+    // The board's minimum is in tiles, like the solver's.
+    let tiles = wordsearch::tile_count(&dict.alphabet, word);
+    if tiles.is_some_and(|n| n < board.min_word_size()) || !scoring.counts(word) {
+        Err(Invalid::TooShort)
+    } else if on_board.contains(word) {
+        Ok(scoring.score(word))
+    } else if !dict.contains(word) {
+        Err(Invalid::NotAWord)
+    } else {
+        Err(Invalid::NotOnBoard)
+    }
+}
+
+pub fn review(dict: &Dictionary, board: &Board, scoring: &Scoring, words: &[String]) -> Review {
+    let all_words: Vec<String> = wordsearch::find_words(dict, board)
+        .into_iter()
+        .filter(|w| scoring.counts(w))
+        .collect();
+    let on_board: HashSet<String> = all_words.iter().cloned().collect();
+
+    let mut found = vec![];
+    let mut invalid = vec![];
+    let mut seen = HashSet::new();
+    for w in words {
+        let w = w.to_lowercase();
+        if !seen.insert(w.clone()) {
+            continue;
+        }
+        match check(dict, board, scoring, &on_board, &w) {
+            Ok(score) => found.push((w, score)),
+            Err(reason) => invalid.push((w, reason)),
+        };
+    }
+
+    let mut by_length = BTreeMap::new();
+    for w in &all_words {
+        by_length.entry(w.chars().count()).or_insert((0, 0)).1 += 1;
+    }
+    for (w, _) in &found {
+        by_length.entry(w.chars().count()).or_insert((0, 0)).0 += 1;
+    }
+
+    let mut missed: Vec<(String, u32)> = all_words
+        .iter()
+        .filter(|w| !seen.contains(*w))
+        .map(|w| (w.clone(), scoring.score(w)))
+        .collect();
+    missed.sort_by(|(a, a_score), (b, b_score)| {
+        b_score
+            .cmp(a_score)
+            .then(b.chars().count().cmp(&a.chars().count()))
+            .then(a.cmp(b))
+    });
+
+    Review {
+        found_score: found.iter().map(|(_, s)| s).sum(),
+        found,
+        invalid,
+        missed,
+        total_words: all_words.len(),
+        total_score: all_words.iter().map(|w| scoring.score(w)).sum(),
+        by_length,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    use super::{check, review, Invalid};
+    use crate::alphabet::Alphabet;
+    use crate::dictionary::{build, build_dictionary};
+    use crate::wordsearch::{boggled, boggled_in, Scoring};
+
+    #[test]
+    fn review_words() {
+        let dict = build_dictionary(vec!["cat", "cats", "scat", "dog", "at"]);
+        let board = boggled("cats\nxxxx\nxxxx\nxxxx\n").unwrap();
+        let words: Vec<String> = ["Cat", "cat", "at", "dog", "tac"]
+            .iter()
+            .map(|w| w.to_string())
+            .collect();
+        let r = review(&dict, &board, &Scoring::default(), &words);
+        assert_eq!(r.found, vec![("cat".to_owned(), 1)]);
+        assert_eq!(
+            r.invalid,
+            vec![
+                ("at".to_owned(), Invalid::TooShort),
+                ("dog".to_owned(), Invalid::NotOnBoard),
+                ("tac".to_owned(), Invalid::NotAWord),
+            ]
+        );
+        assert_eq!(r.missed, vec![("cats".to_owned(), 1)]);
+        assert_eq!(r.total_score, 2);
+        assert_eq!(r.percent(), 50.0);
+        assert_eq!(r.by_length.get(&3), Some(&(1, 1)));
+        assert_eq!(r.by_length.get(&4), Some(&(0, 1)));
+
+        let house = Scoring {
+            min_length: 4,
+            points: vec![3],
+        };
+        let r = review(&dict, &board, &house, &words);
+        assert_eq!(r.found, vec![]);
+        assert_eq!(r.invalid[0], ("cat".to_owned(), Invalid::TooShort));
+        assert_eq!(r.missed, vec![("cats".to_owned(), 3)]);
+        assert_eq!((r.total_words, r.total_score), (1, 3));
+    }
+
+    #[test]
+    fn too_short_in_tiles() {
+        let french = Arc::new(Alphabet::named("french").unwrap());
+        let dict = build(["ete", "quit", "quite"], french.clone());
+        let board = boggled_in("xxxxx\nxxxxx\nxxxxx\nxxxxx\nxxxxx\n", &french).unwrap();
+        let none = HashSet::new();
+        let scoring = Scoring::default();
+        let check = |word| check(&dict, &board, &scoring, &none, word);
+        // Three letters, even with the accents.
+        assert_eq!(check("été"), Err(Invalid::TooShort));
+        // Three tiles, with a Qu.
+        assert_eq!(check("quit"), Err(Invalid::TooShort));
+        assert_eq!(check("quite"), Err(Invalid::NotOnBoard));
+    }
+}
@@ -0,0 +1,65 @@
+use boggle_ocr::config::{ConfigError, Settings, KEYS};
+use boggle_ocr::wordsearch::Scoring;
+use clap::ArgEnum;
+
+/// A flag's value, or else its setting from the config files.
+pub fn or_setting<T: ArgEnum>(
+    flag: Option<T>,
+    settings: &Settings,
+    name: &str,
+) -> Result<T, ConfigError> {
+    if let Some(value) = flag {
+        return Ok(value);
+    }
+    let value = settings.string(name).unwrap_or_default();
+    T::from_str(&value, true).map_err(|err| settings.error(name, err))
+}
+
+/// A setting that counts something, so it can't be negative.
+pub fn count(settings: &Settings, name: &str) -> Result<usize, ConfigError> {
+    let n = settings.integer(name).unwrap_or_default();
+    usize::try_from(n).map_err(|_| settings.error(name, "must not be negative"))
+}
+
+/// The scoring rules from the config files.
+pub fn scoring(settings: &Settings) -> Result<Scoring, ConfigError> {
+    let name = "scoring.points";
+    let points = settings
+        .integers(name)
+        .unwrap_or_default()
+        .into_iter()
+        .map(u32::try_from)
+        .collect::<Result<Vec<u32>, _>>()
+        .map_err(|_| settings.error(name, "points must not be negative"))?;
+    if points.is_empty() {
+        return Err(settings.error(name, "needs at least one entry"));
+    }
+    Ok(Scoring {
+        min_length: count(settings, "scoring.min_length")?,
+        points,
+    })
+}
+
+/// Print every setting as TOML, with where its value comes from. Files with `errors` were left out.
+pub fn show(settings: &Settings, errors: &[ConfigError]) {
+    let files: Vec<String> = settings.files().map(|p| p.display().to_string()).collect();
+    match files.is_empty() {
+        true => println!("# no config files"),
+        false => println!("# config files: {}", files.join(", ")),
+    }
+    for err in errors {
+        println!("# left out: {}", err);
+    }
+    let mut table = "";
+    for key in KEYS {
+        let (t, name) = key.name.split_once('.').unwrap_or(("", key.name));
+        if t != table {
+            println!("\n[{}]", t);
+            table = t;
+        }
+        match settings.get(key.name) {
+            Some((value, source)) => println!("{} = {}  # {}", name, value, source),
+            None => println!("# {} is not set", name),
+        }
+    }
+}
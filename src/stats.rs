@@ -0,0 +1,326 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::{DateTime, Local};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use super::dice::DiceSet;
+use super::dictionary::Dictionary;
+use super::games::Game;
+use super::wordsearch::{self, Scoring};
+
+// Statistics over the whole game log.
+
+#[derive(Serialize, JsonSchema)]
+pub struct Stats {
+    pub games: usize,
+    /// Percentiles of the total score of each board.
+    pub score: Percentiles,
+    /// Percentiles of the number of words on each board.
+    pub words: Percentiles,
+    pub score_histogram: Vec<Bucket>,
+    pub players: Vec<PlayerStats>,
+    /// Words that were on the board but nobody found, most often missed first.
+    pub missed: Vec<MissedWord>,
+    /// How often each letter showed up compared to what the dice should roll, most unusual first.
+    pub letters: Vec<LetterStats>,
+}
+
+#[derive(Serialize, Default, JsonSchema)]
+pub struct Percentiles {
+    pub min: u32,
+    pub p25: u32,
+    pub median: u32,
+    pub p75: u32,
+    pub p90: u32,
+    pub max: u32,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct Bucket {
+    /// The lowest score in this bucket.
+    pub from: u32,
+    /// The highest score in this bucket.
+    pub to: u32,
+    pub count: usize,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct PlayerStats {
+    pub name: String,
+    pub average_score: f64,
+    /// The average percentage of each board's points that the player got.
+    pub average_percent: f64,
+    pub games: Vec<PlayerGame>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct PlayerGame {
+    pub date: DateTime<Local>,
+    pub score: u32,
+    pub percent: f64,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct MissedWord {
+    pub word: String,
+    pub score: u32,
+    /// How many games had this word on the board.
+    pub boards: usize,
+    /// How many of those games nobody found it in.
+    pub missed: usize,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct LetterStats {
+    pub letter: char,
+    pub observed: usize,
+    pub expected: f64,
+    /// How many standard deviations observed is from expected.
+    pub z: f64,
+}
+
+/// Compute statistics for all of the games. Only games with players count toward the missed
+/// words, which are found and scored with `scoring`. The letters are compared with `dice`.
+pub fn compute(
+    games: &[Game],
+    dict: &Dictionary,
+    scoring: &Scoring,
+    dice: DiceSet,
+    missed_count: usize,
+) -> Stats {
+    let scores: Vec<u32> = games.iter().map(|g| g.total_score).collect();
+    let words: Vec<u32> = games.iter().map(|g| g.total_words as u32).collect();
+    Stats {
+        games: games.len(),
+        score: percentiles(&scores),
+        words: percentiles(&words),
+        score_histogram: histogram(&scores, 10),
+        players: players(games),
+        missed: missed(games, dict, scoring, missed_count),
+        letters: letters(games, dice),
+    }
+}
+
+fn percentiles(values: &[u32]) -> Percentiles {
+    if values.is_empty() {
+        return Percentiles::default();
+    }
+    let mut values = values.to_vec();
+    values.sort();
+    // Nearest rank.
+    let p = |pct: usize| values[((pct * values.len()).div_ceil(100)).max(1) - 1];
+    Percentiles {
+        min: values[0],
+        p25: p(25),
+        median: p(50),
+        p75: p(75),
+        p90: p(90),
+        max: values[values.len() - 1],
+    }
+}
+
+fn histogram(values: &[u32], buckets: u32) -> Vec<Bucket> {
+    let (min, max) = match (values.iter().min(), values.iter().max()) {
+        (Some(min), Some(max)) => (*min, *max),
+        _ => return vec![],
+    };
+    let width = (max - min + 1).div_ceil(buckets).max(1);
+    let mut res: Vec<Bucket> = (0..(max - min + 1).div_ceil(width))
+        .map(|i| Bucket {
+            from: min + i * width,
+            to: min + (i + 1) * width - 1,
+            count: 0,
+        })
+        .collect();
+    for v in values {
+        res[((v - min) / width) as usize].count += 1;
+    }
+    res
+}
+
+fn players(games: &[Game]) -> Vec<PlayerStats> {
+    let mut by_name: BTreeMap<&str, Vec<PlayerGame>> = BTreeMap::new();
+    let mut sorted: Vec<&Game> = games.iter().collect();
+    sorted.sort_by_key(|g| g.date);
+    for g in sorted {
+        for p in &g.players {
+            let percent = match g.total_score {
+                0 => 0.0,
+                total => 100.0 * p.score as f64 / total as f64,
+            };
+            by_name.entry(&p.name).or_default().push(PlayerGame {
+                date: g.date,
+                score: p.score,
+                percent,
+            });
+        }
+    }
+    by_name
+        .into_iter()
+        .map(|(name, games)| {
+            let n = games.len() as f64;
+            PlayerStats {
+                name: name.to_owned(),
+                average_score: games.iter().map(|g| g.score as f64).sum::<f64>() / n,
+                average_percent: games.iter().map(|g| g.percent).sum::<f64>() / n,
+                games,
+            }
+        })
+        .collect()
+}
+
+fn missed(games: &[Game], dict: &Dictionary, scoring: &Scoring, count: usize) -> Vec<MissedWord> {
+    let mut counts: HashMap<String, (usize, usize)> = HashMap::new();
+    for g in games.iter().filter(|g| !g.players.is_empty()) {
+        let board = match wordsearch::boggled_in(&g.board, &dict.alphabet) {
+            Ok(board) => board,
+            Err(_) => continue,
+        };
+        let found: HashSet<&String> = g.players.iter().flat_map(|p| &p.words).collect();
+        for w in wordsearch::find_words(dict, &board) {
+            if !scoring.counts(&w) {
+                continue;
+            }
+            let missed = !found.contains(&w);
+            let c = counts.entry(w).or_default();
+            c.0 += 1;
+            if missed {
+                c.1 += 1;
+            }
+        }
+    }
+    let mut res: Vec<MissedWord> = counts
+        .into_iter()
+        .filter(|(_, (_, missed))| *missed > 0)
+        .map(|(word, (boards, missed))| MissedWord {
+            score: scoring.score(&word),
+            word,
+            boards,
+            missed,
+        })
+        .collect();
+    res.sort_by(|a, b| {
+        b.missed
+            .cmp(&a.missed)
+            .then(b.boards.cmp(&a.boards))
+            .then(a.word.cmp(&b.word))
+    });
+    res.truncate(count);
+    res
+}
+
+// Compare letters on the boards with the dice that should have rolled them: `dice` for boards of
+// its size, and otherwise Boggle for 4x4, Big Boggle for 5x5, and Super Big Boggle for 6x6.
+fn letters(games: &[Game], dice: DiceSet) -> Vec<LetterStats> {
+    // Every letter starts at zero, so that letters that never came up are listed too.
+    let mut observed: BTreeMap<char, usize> = ('A'..='Z').map(|ch| (ch, 0)).collect();
+    let mut expected: BTreeMap<char, (f64, f64)> = BTreeMap::new();
+    for g in games {
+        let letters: Vec<char> = g
+            .board
+            .chars()
+            .filter(|ch| ch.is_alphabetic())
+            .map(upper)
+            .collect();
+        let set = [dice, DiceSet::New, DiceSet::Big, DiceSet::SuperBig]
+            .into_iter()
+            .find(|set| set.size() * set.size() == letters.len());
+        let set = match set {
+            Some(set) => set,
+            None => continue,
+        };
+        for ch in letters {
+            *observed.entry(ch).or_default() += 1;
+        }
+        for die in set.dice() {
+            let faces: Vec<char> = die.chars().filter(|ch| *ch != '*').collect();
+            let unique: HashSet<char> = faces.iter().copied().collect();
+            for ch in unique {
+                let p = faces.iter().filter(|f| **f == ch).count() as f64 / faces.len() as f64;
+                let (e, v) = expected.entry(ch).or_default();
+                *e += p;
+                *v += p * (1.0 - p);
+            }
+        }
+    }
+    for ch in expected.keys() {
+        observed.entry(*ch).or_default();
+    }
+    let mut res: Vec<LetterStats> = observed
+        .into_iter()
+        .map(|(letter, observed)| {
+            let (expected, variance) = expected.get(&letter).copied().unwrap_or_default();
+            LetterStats {
+                letter,
+                observed,
+                expected,
+                z: match variance {
+                    v if v > 0.0 => (observed as f64 - expected) / v.sqrt(),
+                    _ => 0.0,
+                },
+            }
+        })
+        .collect();
+    res.sort_by(|a, b| b.z.abs().total_cmp(&a.z.abs()));
+    res
+}
+
+// A letter as it's written on a board, in upper case if that's still one letter. (ß isn't.)
+fn upper(ch: char) -> char {
+    let mut upper = ch.to_uppercase();
+    match (upper.next(), upper.next()) {
+        (Some(up), None) => up,
+        _ => ch,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Local;
+
+    use super::{histogram, letters, percentiles};
+    use crate::dice::DiceSet;
+    use crate::games::Game;
+
+    #[test]
+    fn percentiles_of_ten() {
+        let p = percentiles(&[10, 1, 9, 2, 8, 3, 7, 4, 6, 5]);
+        assert_eq!(
+            (p.min, p.p25, p.median, p.p75, p.p90, p.max),
+            (1, 3, 5, 8, 9, 10)
+        );
+    }
+
+    #[test]
+    fn histogram_buckets() {
+        let h = histogram(&[0, 5, 9, 10, 19], 2);
+        let h: Vec<(u32, u32, usize)> = h.into_iter().map(|b| (b.from, b.to, b.count)).collect();
+        assert_eq!(h, vec![(0, 9, 3), (10, 19, 2)]);
+    }
+
+    #[test]
+    fn letters_beyond_ascii() {
+        let game = |board: &str| Game {
+            date: Local::now(),
+            board: board.to_owned(),
+            total_words: 0,
+            total_score: 0,
+            players: vec![],
+        };
+        let games = [game("Ñaba\nxxxx\nxxxx\nxxxx\n"), game("ABCD\nEFGH\n")];
+        let stats = letters(&games, DiceSet::Classic);
+        let count = |ch| stats.iter().find(|l| l.letter == ch).unwrap().observed;
+        assert_eq!(count('Ñ'), 1);
+        assert_eq!(count('A'), 2);
+        assert_eq!(count('X'), 12);
+        assert_eq!(count('Z'), 0);
+        // Five of the classic dice have one T, where the new ones have nine.
+        let t = stats.iter().find(|l| l.letter == 'T').unwrap();
+        assert!((t.expected - 5.0 / 6.0).abs() < 1e-9);
+        assert_eq!(
+            stats.iter().find(|l| l.letter == 'Ñ').unwrap().expected,
+            0.0
+        );
+    }
+}
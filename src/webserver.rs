@@ -1,21 +1,38 @@
-use std::sync::Arc;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+use std::time::{Duration, Instant, SystemTime};
 
-use axum::extract::{Path, Query, State};
-use axum::http::StatusCode;
-use axum::response::IntoResponse;
-use axum::routing::get;
+use axum::extract::rejection::JsonRejection;
+use axum::extract::{MatchedPath, Path, Query, Request, State};
+use axum::http::{header, HeaderMap, HeaderName, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
 use axum::{Json, Router};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use subtle::ConstantTimeEq;
 use tokio::runtime::Runtime;
 use tower_http::services::ServeDir;
 use tower_http::trace::{DefaultMakeSpan, TraceLayer};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-use crate::dictionary::{self, Definitions, Dictionary};
+use boggle_ocr::api::{self, DictInfo, ErrorBody};
+use boggle_ocr::config::Settings;
+use boggle_ocr::dice::DiceSet;
+use boggle_ocr::dictionary::{self, Definitions, Dictionary};
+use boggle_ocr::wordsearch::{Board, Scoring, Solution, SolveOptions, SolvedWord};
+use boggle_ocr::{games, overlay, stats, wordsearch};
+
+use crate::cache::{CacheStats, Lru};
+use crate::metrics::{Metrics, Text};
+use crate::openapi;
 use crate::options::ServerOptions;
-use crate::wordsearch;
+use crate::settings;
 
-pub fn serve(opts: ServerOptions) -> Result<(), Box<dyn std::error::Error>> {
+pub fn serve(opts: ServerOptions, settings: &Settings) -> Result<(), Box<dyn std::error::Error>> {
     tracing_subscriber::registry()
         .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
@@ -29,106 +46,824 @@ pub fn serve(opts: ServerOptions) -> Result<(), Box<dyn std::error::Error>> {
         assets,
         dict,
         defs,
+        overlay,
+        dicts,
+        games,
+        watch,
+        admin_token,
+        cache_mb,
     } = opts;
 
-    let addr = addr.unwrap_or("127.0.0.1:0".to_owned());
-    let assets = assets.unwrap_or("assets".to_owned());
-    let dict = dict.unwrap_or("cached.dict".to_owned());
-    let defs = defs.unwrap_or("DICT.json".to_owned());
+    let addr = addr
+        .or_else(|| settings.string("server.addr"))
+        .unwrap_or_default();
+    let assets = match assets {
+        Some(assets) => assets,
+        None => settings
+            .path("server.assets")
+            .map(|(path, _)| path.display().to_string())
+            .unwrap_or_default(),
+    };
+    let games = games.unwrap_or("games.jsonl".to_owned());
+    let cache_mb = match cache_mb {
+        Some(mb) => mb,
+        None => settings::count(settings, "server.cache_mb")?,
+    };
 
-    tracing::debug!("reading DAG...");
-    let dict = dictionary::read(&dict)?;
-    tracing::debug!("reading definitions...");
-    let defs = dictionary::open_defs_path(&defs)?;
-    tracing::debug!("loaded {} words", defs.len());
+    let source = match dicts {
+        Some(path) => DictSource::File(path),
+        None => DictSource::Single(DictConfig {
+            name: "default".to_owned(),
+            dict: match dict {
+                Some(dict) => dict,
+                None => dictionary::default_dict_path()?,
+            },
+            defs: Some(defs.unwrap_or_else(dictionary::default_defs_path)),
+            overlay,
+        }),
+    };
+    // The dictionaries are loaded after the server starts, but a missing --dicts file can fail
+    // right away.
+    source.configs()?;
+
+    let data = Data {
+        dicts: Default::default(),
+        source: source.into(),
+        reloading: Default::default(),
+        admin_token: admin_token.map(Arc::new),
+        games: games.into(),
+        scoring: settings::scoring(settings)?.into(),
+        dice: settings::or_setting(None, settings, "board.dice")?,
+        cache: Arc::new(Mutex::new(Lru::new(cache_mb.saturating_mul(1 << 20)))),
+        metrics: Default::default(),
+    };
 
     let rt = Runtime::new()?;
-    rt.block_on(async move { async_serve(addr, assets, dict, defs).await });
+    rt.block_on(async move { async_serve(addr, assets, data, watch).await })?;
     Ok(())
 }
 
-async fn async_serve(addr: String, assets_dir: String, dict: Dictionary, defs: Definitions) {
+/// Where the dictionaries come from: a --dicts file, or just --dict and --defs.
+enum DictSource {
+    File(String),
+    Single(DictConfig),
+}
+
+impl DictSource {
+    fn configs(&self) -> Result<Vec<DictConfig>, Box<dyn std::error::Error>> {
+        match self {
+            DictSource::File(path) => Ok(serde_json::from_reader(std::fs::File::open(path)?)?),
+            DictSource::Single(config) => Ok(vec![config.clone()]),
+        }
+    }
+
+    // All of the files that a reload would read. A change to any of them triggers a reload when
+    // watching.
+    fn paths(&self) -> Vec<String> {
+        let mut paths = vec![];
+        if let DictSource::File(path) = self {
+            paths.push(path.clone());
+        }
+        for config in self.configs().unwrap_or_default() {
+            paths.push(config.dict);
+            paths.extend(config.defs);
+            paths.extend(config.overlay);
+        }
+        paths
+    }
+}
+
+/// One entry in the --dicts file, which is a JSON list of these. The first one is the default.
+#[derive(Clone, Deserialize)]
+struct DictConfig {
+    name: String,
+    /// The compiled dictionary.
+    dict: String,
+    /// The compiled definitions or the full JSON dictionary. Without it, words have no definitions.
+    defs: Option<String>,
+    /// House rules on top of the dictionary.
+    overlay: Option<String>,
+}
+
+struct NamedDict {
+    // Different for every load, so that solutions cached before a reload aren't used after it.
+    id: u64,
+    name: String,
+    dict: Dictionary,
+    defs: Definitions,
+    words: usize,
+}
+
+/// Every loaded dictionary. The first one is the default.
+struct Dicts(Vec<NamedDict>);
+
+impl Dicts {
+    // Find a dictionary by name, or get the default one.
+    fn get(&self, name: &Option<String>) -> Result<&NamedDict, ApiError> {
+        match name {
+            None => Ok(&self.0[0]),
+            Some(name) => match self.0.iter().find(|d| d.name == *name) {
+                Some(d) => Ok(d),
+                None => Err(ApiError::UnknownDict(name.clone())),
+            },
+        }
+    }
+
+    fn info(&self) -> Vec<DictInfo> {
+        self.0
+            .iter()
+            .enumerate()
+            .map(|(i, d)| DictInfo {
+                name: d.name.clone(),
+                words: d.words,
+                definitions: d.defs.len(),
+                default: i == 0,
+            })
+            .collect()
+    }
+}
+
+/// Read and check all of the dictionaries. Any failure fails the whole load, so that a reload
+/// never leaves the server with half of a new set of dictionaries.
+fn load_dicts(configs: &[DictConfig]) -> Result<Dicts, Box<dyn std::error::Error>> {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+    if configs.is_empty() {
+        return Err("no dictionaries configured".into());
+    }
+    let mut dicts = vec![];
+    for config in configs {
+        if dicts.iter().any(|d: &NamedDict| d.name == config.name) {
+            return Err(format!("more than one dictionary is named {:?}", config.name).into());
+        }
+        tracing::debug!("{}: reading DAG from {}...", config.name, config.dict);
+        let mut dict = dictionary::read(&config.dict)
+            .map_err(|e| format!("{}: {}: {}", config.name, config.dict, e))?;
+        if let Some(path) = &config.overlay {
+            tracing::debug!("{}: reading overlay from {}...", config.name, path);
+            let overlay = overlay::open(path, &dict.alphabet)
+                .map_err(|e| format!("{}: {}: {}", config.name, path, e))?;
+            dict = dict.with_overlay(overlay);
+        }
+        let defs = match &config.defs {
+            Some(path) => {
+                tracing::debug!("{}: reading definitions from {}...", config.name, path);
+                dictionary::open_defs_path(path)
+                    .map_err(|e| format!("{}: {}: {}", config.name, path, e))?
+            }
+            None => Definitions::new(),
+        };
+        let words = dict.word_count();
+        if words == 0 {
+            return Err(format!("{}: {} has no words", config.name, config.dict).into());
+        }
+        tracing::debug!("{}: loaded {} words", config.name, words);
+        dicts.push(NamedDict {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            name: config.name.clone(),
+            dict,
+            defs,
+            words,
+        });
+    }
+    Ok(Dicts(dicts))
+}
+
+async fn async_serve(
+    addr: String,
+    assets_dir: String,
+    data: Data,
+    watch: Option<u64>,
+) -> Result<(), Box<dyn std::error::Error>> {
     let app = Router::new()
         .fallback_service(ServeDir::new(assets_dir).append_index_html_on_directories(true))
         .route("/boggle/solver/solution", get(solve_boggle))
+        .route("/api/v1/boggle/solve", post(api_solve))
+        .route("/api/openapi.json", get(openapi_document))
         .route("/boggle/dict/words/:word", get(boggle_word))
+        .route("/boggle/stats/data", get(boggle_stats))
+        .route("/dicts", get(list_dicts))
+        .route("/cache", get(cache_stats))
+        .route("/admin/reload", post(admin_reload))
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .route("/metrics", get(metrics))
+        .layer(middleware::from_fn_with_state(data.clone(), track))
         .layer(
             TraceLayer::new_for_http()
                 .make_span_with(DefaultMakeSpan::default().include_headers(true)),
         )
-        .with_state(Data {
-            dict: dict.into(),
-            defs: defs.into(),
-        });
+        .with_state(data.clone());
+
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .map_err(|e| std::io::Error::new(e.kind(), format!("{}: {}", addr, e)))?;
+    tracing::info!("listening on {}", listener.local_addr()?);
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    // /readyz says the server isn't ready until this is done. If it fails, there's nothing to
+    // serve, so the server stops.
+    let dicts = data.load().await?;
+    tracing::info!("loaded {} dictionaries, ready", dicts.0.len());
+    #[cfg(unix)]
+    tokio::spawn(reload_on_hangup(data.clone()));
+    if let Some(secs) = watch {
+        tokio::spawn(reload_on_change(data.clone(), Duration::from_secs(secs)));
+    }
 
-    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    tracing::info!("listening on {}", listener.local_addr().unwrap());
-    axum::serve(listener, app).await.unwrap();
+    Ok(server.await??)
+}
+
+// Count requests and how long they take, by route.
+async fn track(State(data): State<Data>, request: Request, next: Next) -> Response {
+    let start = Instant::now();
+    let route = match request.extensions().get::<MatchedPath>() {
+        Some(path) => path.as_str().to_owned(),
+        // Static files, and anything else that isn't an endpoint.
+        None => "assets".to_owned(),
+    };
+    let method = request.method().clone();
+    let response = next.run(request).await;
+    let status = response.status().as_u16();
+    data.metrics
+        .request(&route, method.as_str(), status, start.elapsed());
+    response
 }
 
 #[derive(Clone)]
 struct Data {
-    dict: Arc<dictionary::Dictionary>,
-    defs: Arc<dictionary::Definitions>,
+    // None until the first load finishes.
+    dicts: Arc<RwLock<Option<Arc<Dicts>>>>,
+    source: Arc<DictSource>,
+    // Held while reloading so that two reloads don't race.
+    reloading: Arc<Mutex<()>>,
+    admin_token: Option<Arc<String>>,
+    games: Arc<String>,
+    // The scoring rules for API requests that don't have their own.
+    scoring: Arc<Scoring>,
+    // The dice that /boggle/stats compares the letters with.
+    dice: DiceSet,
+    cache: Arc<Mutex<SolutionCache>>,
+    metrics: Arc<Metrics>,
+}
+
+type SolutionCache = Lru<SolutionKey, Solution>;
+
+/// What a solution depends on. The board is normalized, so "abcd" and "A B C D" are the same.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct SolutionKey {
+    dict: u64,
+    board: Vec<String>,
+    scoring: Scoring,
+}
+
+impl Data {
+    // The current dictionaries. Requests hold on to these, so a reload doesn't change the
+    // dictionary in the middle of a request.
+    fn dicts(&self) -> Result<Arc<Dicts>, ApiError> {
+        // A panic while holding the lock can't leave a half-swapped Arc behind.
+        self.dicts
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+            .ok_or(ApiError::NotReady)
+    }
+
+    // Load the dictionaries and swap them in if they're all OK.
+    async fn load(&self) -> Result<Arc<Dicts>, String> {
+        let data = self.clone();
+        tokio::task::spawn_blocking(move || {
+            let _lock = data
+                .reloading
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            let start = Instant::now();
+            let dicts = match data
+                .source
+                .configs()
+                .and_then(|configs| load_dicts(&configs))
+            {
+                Ok(dicts) => Arc::new(dicts),
+                Err(e) => {
+                    data.metrics.load_failed();
+                    return Err(e.to_string());
+                }
+            };
+            data.metrics.loaded(start.elapsed());
+            *data.dicts.write().unwrap_or_else(PoisonError::into_inner) = Some(dicts.clone());
+            data.cache().clear();
+            Ok(dicts)
+        })
+        .await
+        .unwrap_or_else(|e| Err(e.to_string()))
+    }
+
+    async fn reload(&self) -> Result<Arc<Dicts>, String> {
+        let res = self.load().await;
+        match &res {
+            Ok(dicts) => tracing::info!("reloaded {} dictionaries", dicts.0.len()),
+            Err(e) => tracing::error!("reload failed, keeping the old dictionaries: {}", e),
+        };
+        res
+    }
+
+    fn cache(&self) -> std::sync::MutexGuard<'_, SolutionCache> {
+        self.cache.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // Solve a board, or remember the solution from an earlier request. Solutions have every word,
+    // with its path and definition, so that any request for the board can be answered from one.
+    // Also says whether the solution was cached.
+    fn solve(&self, dict: &NamedDict, board: &Board, scoring: &Scoring) -> (Arc<Solution>, bool) {
+        let key = SolutionKey {
+            dict: dict.id,
+            board: board.rows_in(&dict.dict.alphabet),
+            scoring: scoring.clone(),
+        };
+        if let Some(solution) = self.cache().get(&key) {
+            return (solution, true);
+        }
+        // Other requests for the same board don't wait for this one. At worst they solve it too.
+        let opts = SolveOptions {
+            defs: Some(&dict.defs),
+            scoring: Some(scoring),
+            ..Default::default()
+        };
+        let start = Instant::now();
+        let solution = Arc::new(wordsearch::solve(&dict.dict, board, &opts));
+        self.metrics.solve(start.elapsed());
+        let bytes = solution_bytes(&key, &solution);
+        self.cache().insert(key, solution.clone(), bytes);
+        (solution, false)
+    }
+
+    /// `solve` where a big board or a run of new boards won't hold up other requests.
+    async fn solve_blocking(
+        &self,
+        dicts: Arc<Dicts>,
+        dict: Option<String>,
+        board: Board,
+        scoring: Scoring,
+    ) -> Result<(Arc<Solution>, bool), ApiError> {
+        let data = self.clone();
+        tokio::task::spawn_blocking(move || {
+            let dict = dicts.get(&dict)?;
+            Ok(data.solve(dict, &board, &scoring))
+        })
+        .await
+        .unwrap_or_else(|e| Err(ApiError::Internal(e.to_string())))
+    }
+}
+
+// Roughly how much memory a cached solution takes.
+fn solution_bytes(key: &SolutionKey, solution: &Solution) -> usize {
+    use std::mem::size_of;
+    let rows: usize = key
+        .board
+        .iter()
+        .map(|row| size_of::<String>() + row.len())
+        .sum();
+    let words: usize = solution
+        .words
+        .iter()
+        .map(|w| {
+            size_of::<SolvedWord>()
+                + w.word.len()
+                + std::mem::size_of_val(w.path.as_slice())
+                + w.def.as_ref().map_or(0, String::len)
+        })
+        .sum();
+    size_of::<SolutionKey>() + size_of::<Solution>() + 2 * rows + words
+}
+
+/// How long browsers can use a solution without asking again. After that they check it with
+/// its ETag, which only changes if the dictionary does.
+const MAX_AGE: u32 = 300;
+
+static X_CACHE: HeaderName = HeaderName::from_static("x-cache");
+
+// A JSON response that browsers can cache. If the browser already has it, the response is just
+// "304 Not Modified".
+fn cacheable_json(headers: &HeaderMap, body: Vec<u8>, cached: bool) -> Response {
+    let mut hasher = DefaultHasher::new();
+    body.hash(&mut hasher);
+    let etag = format!("\"{:016x}\"", hasher.finish());
+    let unchanged = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| {
+            v.split(',').any(|tag| {
+                let tag = tag.trim().trim_start_matches("W/");
+                tag == "*" || tag == etag
+            })
+        });
+    let headers = [
+        (header::ETAG, etag),
+        (
+            header::CACHE_CONTROL,
+            format!("public, max-age={}", MAX_AGE),
+        ),
+        (X_CACHE.clone(), hit_or_miss(cached).to_owned()),
+    ];
+    if unchanged {
+        return (StatusCode::NOT_MODIFIED, headers).into_response();
+    }
+    (headers, [(header::CONTENT_TYPE, "application/json")], body).into_response()
+}
+
+fn hit_or_miss(cached: bool) -> &'static str {
+    match cached {
+        true => "HIT",
+        false => "MISS",
+    }
+}
+
+#[cfg(unix)]
+async fn reload_on_hangup(data: Data) {
+    use tokio::signal::unix::{signal, SignalKind};
+    let mut hangups = match signal(SignalKind::hangup()) {
+        Ok(s) => s,
+        Err(e) => {
+            tracing::error!("can't listen for SIGHUP: {}", e);
+            return;
+        }
+    };
+    while hangups.recv().await.is_some() {
+        tracing::info!("got SIGHUP, reloading dictionaries");
+        let _ = data.reload().await;
+    }
+}
+
+// The modification times of all of the files that a reload would read.
+fn mtimes(source: &DictSource) -> Vec<Option<SystemTime>> {
+    source
+        .paths()
+        .iter()
+        .map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+        .collect()
+}
+
+// Poll the dictionary files and reload when any of them change.
+async fn reload_on_change(data: Data, every: Duration) {
+    let source = data.source.clone();
+    let check = move || {
+        let source = source.clone();
+        tokio::task::spawn_blocking(move || mtimes(&source))
+    };
+    let Ok(mut last) = check().await else {
+        return;
+    };
+    let mut interval = tokio::time::interval(every);
+    loop {
+        interval.tick().await;
+        let Ok(now) = check().await else {
+            continue;
+        };
+        if now != last {
+            tracing::info!("dictionary files changed, reloading");
+            let _ = data.reload().await;
+            last = now;
+        }
+    }
+}
+
+async fn admin_reload(
+    headers: HeaderMap,
+    State(data): State<Data>,
+) -> Result<Json<Vec<DictInfo>>, ApiError> {
+    let token = data.admin_token.as_ref().ok_or(ApiError::AdminDisabled)?;
+    let given = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    // Compare in constant time, so that the response time doesn't say how much of a guess was
+    // right.
+    let matches = given.is_some_and(|given| bool::from(given.as_bytes().ct_eq(token.as_bytes())));
+    if !matches {
+        return Err(ApiError::Unauthorized);
+    }
+    match data.reload().await {
+        Ok(dicts) => Ok(Json(dicts.info())),
+        Err(e) => Err(ApiError::ReloadFailed(e)),
+    }
 }
 
-#[derive(Deserialize)]
-struct SolveBoggleRequest {
+/// Why a request failed. The response body is JSON, e.g.
+/// `{"error": "bad_tile", "message": "...", "row": 2, "col": 3}`.
+#[derive(Debug)]
+enum ApiError {
+    UnknownDict(String),
+    BadRequest(String),
+    BadBoard(wordsearch::WSError),
+    NotAWord { word: String, note: Option<String> },
+    AdminDisabled,
+    Unauthorized,
+    ReloadFailed(String),
+    NotReady,
+    Internal(String),
+}
+
+impl ApiError {
+    fn status(&self) -> StatusCode {
+        match self {
+            ApiError::UnknownDict(_) | ApiError::NotAWord { .. } | ApiError::AdminDisabled => {
+                StatusCode::NOT_FOUND
+            }
+            ApiError::BadRequest(_) | ApiError::BadBoard(_) => StatusCode::BAD_REQUEST,
+            ApiError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ApiError::ReloadFailed(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::NotReady => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn code(&self) -> &'static str {
+        match self {
+            ApiError::UnknownDict(_) => "unknown_dict",
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::BadBoard(wordsearch::WSError::BadTile { .. }) => "bad_tile",
+            ApiError::BadBoard(wordsearch::WSError::WrongTileCount(_)) => "wrong_tile_count",
+            ApiError::NotAWord { .. } => "not_a_word",
+            ApiError::AdminDisabled => "not_found",
+            ApiError::Unauthorized => "unauthorized",
+            ApiError::ReloadFailed(_) => "reload_failed",
+            ApiError::NotReady => "not_ready",
+            ApiError::Internal(_) => "internal",
+        }
+    }
+
+    fn body(self) -> ErrorBody {
+        let mut body = ErrorBody {
+            error: self.code().to_owned(),
+            message: self.to_string(),
+            row: None,
+            col: None,
+            note: None,
+        };
+        match self {
+            ApiError::BadBoard(wordsearch::WSError::BadTile { row, col, .. }) => {
+                body.row = Some(row);
+                body.col = Some(col);
+            }
+            ApiError::NotAWord { note, .. } => body.note = note,
+            _ => (),
+        }
+        body
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiError::UnknownDict(name) => write!(f, "no dictionary named {:?}", name),
+            ApiError::BadBoard(e) => write!(f, "{}", e),
+            ApiError::NotAWord { word, .. } => write!(f, "{} is not a word", word),
+            ApiError::AdminDisabled => write!(f, "no admin token is configured"),
+            ApiError::Unauthorized => write!(f, "wrong or missing token"),
+            ApiError::NotReady => write!(f, "the dictionaries are still loading"),
+            ApiError::BadRequest(e) | ApiError::ReloadFailed(e) | ApiError::Internal(e) => {
+                write!(f, "{}", e)
+            }
+        }
+    }
+}
+
+impl From<api::RequestError> for ApiError {
+    fn from(err: api::RequestError) -> Self {
+        match err {
+            api::RequestError::Board(e) => ApiError::BadBoard(e),
+            e => ApiError::BadRequest(e.to_string()),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        if status.is_server_error() {
+            tracing::error!("{:?}", self);
+        }
+        (status, Json(self.body())).into_response()
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct DictQuery {
+    /// A dictionary name from /dicts. Defaults to the default dictionary.
+    dict: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct SolveBoggleRequest {
+    /// The board's letters, row by row. Whitespace is ignored.
     board: String,
+    /// How many of the best words to list. Defaults to 20.
     best_words_count: Option<usize>,
+    /// A dictionary name from /dicts. Defaults to the default dictionary.
+    dict: Option<String>,
 }
 
-#[derive(Serialize)]
-struct SolveBoggleResponse {
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct SolveBoggleResponse {
     total_words: usize,
     total_score: u32,
     best_words: Vec<ScoredBoggleWord>,
 }
 
-#[derive(Serialize)]
-struct ScoredBoggleWord {
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct ScoredBoggleWord {
     word: String,
     score: u32,
     def: Option<String>,
 }
 
 async fn solve_boggle(
+    headers: HeaderMap,
     Query(query): Query<SolveBoggleRequest>,
     State(data): State<Data>,
-) -> impl IntoResponse {
+) -> Result<Response, ApiError> {
     let SolveBoggleRequest {
         board,
         best_words_count,
+        dict,
     } = query;
 
-    let board = match wordsearch::boggled(board.trim()) {
-        Ok(b) => b,
-        Err(e) => return (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
-    };
+    let dicts = data.dicts()?;
+    let alphabet = &dicts.get(&dict)?.dict.alphabet;
 
-    let words = wordsearch::find_words(&data.dict, &board);
+    let board = wordsearch::boggled_in(board.trim(), alphabet).map_err(ApiError::BadBoard)?;
 
-    let total_score = words.iter().map(|w| wordsearch::score(w)).sum();
+    let scoring = Scoring::clone(&data.scoring);
+    let (solution, cached) = data
+        .solve_blocking(dicts.clone(), dict, board, scoring)
+        .await?;
 
-    let best_words = wordsearch::best_words(&words, best_words_count.or(Some(20)))
-        .into_iter()
-        .map(|(word, score)| {
-            let def = data.defs.get(&word).cloned();
-            ScoredBoggleWord { word, score, def }
+    let best_words = solution
+        .words
+        .iter()
+        .take(best_words_count.unwrap_or(20))
+        .map(|w| ScoredBoggleWord {
+            word: w.word.clone(),
+            score: w.score,
+            def: w.def.clone(),
         })
         .collect();
 
-    Json(SolveBoggleResponse {
-        total_words: words.len(),
-        total_score,
+    let body = serde_json::to_vec(&SolveBoggleResponse {
+        total_words: solution.total_words,
+        total_score: solution.total_score,
         best_words,
     })
-    .into_response()
+    .map_err(|e| ApiError::Internal(e.to_string()))?;
+    Ok(cacheable_json(&headers, body, cached))
+}
+
+async fn api_solve(
+    State(data): State<Data>,
+    body: Result<Json<api::SolveRequest>, JsonRejection>,
+) -> Result<impl IntoResponse, ApiError> {
+    let Json(request) = body.map_err(|e| ApiError::BadRequest(e.body_text()))?;
+    let dicts = data.dicts()?;
+    let dict = dicts.get(&request.dict)?;
+    let board = request.board_in(&dict.dict.alphabet)?;
+    let scoring = request.rules(&data.scoring, &board)?;
+    let (solution, cached) = data
+        .solve_blocking(dicts.clone(), request.dict.clone(), board, scoring.clone())
+        .await?;
+    let res = api::respond(&dict.name, &request, scoring, &solution);
+    Ok(([(X_CACHE.clone(), hit_or_miss(cached))], Json(res)))
+}
+
+async fn openapi_document() -> Json<serde_json::Value> {
+    Json(openapi::document())
+}
+
+async fn boggle_word(
+    Path(word): Path<String>,
+    Query(query): Query<DictQuery>,
+    State(data): State<Data>,
+) -> Result<String, ApiError> {
+    let dicts = data.dicts()?;
+    let dict = dicts.get(&query.dict)?;
+    let word = word.to_lowercase();
+    if !dict.dict.contains(&word) {
+        // A banned word's note says why.
+        let note = dict.dict.note(&word).map(str::to_owned);
+        return Err(ApiError::NotAWord { word, note });
+    }
+    // Dictionaries without definitions can still say whether a word is in them.
+    let def = dict.dict.note(&word).or(dict.defs.get(&word));
+    Ok(def.unwrap_or_default().to_owned())
+}
+
+async fn list_dicts(State(data): State<Data>) -> Result<Json<Vec<DictInfo>>, ApiError> {
+    Ok(Json(data.dicts()?.info()))
+}
+
+async fn cache_stats(State(data): State<Data>) -> Json<CacheStats> {
+    Json(data.cache().stats())
+}
+
+// The server is up, though it may still be loading.
+async fn healthz() -> &'static str {
+    "ok\n"
+}
+
+// The server can answer requests.
+async fn readyz(State(data): State<Data>) -> Result<&'static str, ApiError> {
+    data.dicts()?;
+    Ok("ready\n")
 }
 
-async fn boggle_word(Path(word): Path<String>, State(data): State<Data>) -> impl IntoResponse {
-    match data.defs.get(&word.to_lowercase()) {
-        Some(def) => (StatusCode::OK, def.to_owned()),
-        None => (StatusCode::NOT_FOUND, "".to_owned()),
+async fn metrics(State(data): State<Data>) -> impl IntoResponse {
+    let mut text = Text::default();
+    data.metrics.write(&mut text);
+
+    let ready = data.dicts().ok();
+    let name = "boggle_ocr_ready";
+    text.family(name, "gauge", "1 once the dictionaries are loaded.");
+    text.sample(name, &[], ready.is_some() as u8 as f64);
+    let dicts = ready.as_ref().map_or(&[][..], |dicts| &dicts.0);
+    let name = "boggle_ocr_dictionary_words";
+    text.family(name, "gauge", "Words in each dictionary.");
+    for dict in dicts {
+        text.sample(name, &[("dict", &dict.name)], dict.words as f64);
     }
+    let name = "boggle_ocr_dictionary_definitions";
+    text.family(name, "gauge", "Definitions in each dictionary.");
+    for dict in dicts {
+        text.sample(name, &[("dict", &dict.name)], dict.defs.len() as f64);
+    }
+
+    let stats = data.cache().stats();
+    for (name, kind, help, value) in [
+        (
+            "boggle_ocr_cache_hits_total",
+            "counter",
+            "Solutions that came from the cache.",
+            stats.hits as f64,
+        ),
+        (
+            "boggle_ocr_cache_misses_total",
+            "counter",
+            "Boards that weren't in the cache.",
+            stats.misses as f64,
+        ),
+        (
+            "boggle_ocr_cache_evictions_total",
+            "counter",
+            "Solutions dropped to make room.",
+            stats.evictions as f64,
+        ),
+        (
+            "boggle_ocr_cache_entries",
+            "gauge",
+            "Solutions in the cache.",
+            stats.entries as f64,
+        ),
+        (
+            "boggle_ocr_cache_bytes",
+            "gauge",
+            "The estimated size of the cache.",
+            stats.bytes as f64,
+        ),
+        (
+            "boggle_ocr_cache_max_bytes",
+            "gauge",
+            "The most the cache can hold.",
+            stats.max_bytes as f64,
+        ),
+    ] {
+        text.family(name, kind, help);
+        text.sample(name, &[], value);
+    }
+
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        text.finish(),
+    )
+}
+
+async fn boggle_stats(
+    Query(query): Query<DictQuery>,
+    State(data): State<Data>,
+) -> Result<Json<stats::Stats>, ApiError> {
+    let dicts = data.dicts()?;
+    dicts.get(&query.dict)?;
+    // This solves every logged board, so it runs where it won't hold up other requests.
+    tokio::task::spawn_blocking(move || {
+        let dict = dicts.get(&query.dict)?;
+        match games::load(data.games.as_str()) {
+            Ok(games) => Ok(Json(stats::compute(
+                &games,
+                &dict.dict,
+                &data.scoring,
+                data.dice,
+                20,
+            ))),
+            Err(e) => Err(ApiError::Internal(e.to_string())),
+        }
+    })
+    .await
+    .unwrap_or_else(|e| Err(ApiError::Internal(e.to_string())))
 }
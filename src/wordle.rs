@@ -1,37 +1,107 @@
-use super::dictionary::{self, letter_for_pos, letter_pos, Dictionary, Letter};
+use super::alphabet::Alphabet;
+use super::dictionary::{self, Dictionary, Letter};
 use std::collections::HashSet;
 
+/// Find the five letter words that fit the clues. Letters are tiles of the dictionary's alphabet,
+/// so a Dutch IJ is one letter.
+///
+/// ```
+/// use boggle_ocr::{build_dictionary, solve_wordle, wordle_clues, Alphabet};
+///
+/// let dict = build_dictionary(vec!["crane", "crate", "slate", "trace"]);
+/// let clues = wordle_clues(&Alphabet::english(), Some("cr---"), Some("t"), Some("n"))?;
+/// assert_eq!(solve_wordle(&clues, &dict), vec!["crate"]);
+/// # Ok::<(), boggle_ocr::ClueError>(())
+/// ```
 pub fn run(clues: &[Clue], dict: &Dictionary) -> Vec<String> {
     let mut known = [None; 5];
     let mut include = vec![];
-    let mut ok = [true; 26];
+    let mut ok = vec![true; dict.alphabet.len()];
     for c in clues {
         match c {
-            Clue::Gray(ch) => ok[letter_pos(*ch).i()] = false,
-            Clue::Yellow(ch) => include.push(letter_pos(*ch)),
-            Clue::Green(ch, i) => known[*i] = Some(letter_pos(*ch)),
+            Clue::Gray(l) if l.i() < ok.len() => ok[l.i()] = false,
+            Clue::Gray(_) => {}
+            Clue::Yellow(l) => include.push(*l),
+            Clue::Green(l, i) => known[*i] = Some(*l),
         };
     }
     let mut res = HashSet::new();
-    search(
-        &mut res,
-        &mut [Letter::empty(); 5],
-        0,
-        &known,
-        &include,
-        &ok,
-        &dict.root,
-    );
-    res.into_iter().collect()
+    for root in dict.roots() {
+        search(
+            dict,
+            &mut res,
+            &mut [Letter::empty(); 5],
+            0,
+            &known,
+            &include,
+            &ok,
+            root,
+        );
+    }
+    res.into_iter().filter(|w| dict.allowed(w)).collect()
 }
 
 pub enum Clue {
-    Gray(char),
-    Yellow(char),
-    Green(char, usize),
+    Gray(Letter),
+    Yellow(Letter),
+    Green(Letter, usize),
+}
+
+/// Turn the letters from a guess into clues, with one character per tile of `alphabet` like a
+/// board. `pattern` has the green letters in place and '-' for the rest, e.g. "c-a--". `include`
+/// has the yellow letters and `exclude` has the gray ones.
+pub fn clues(
+    alphabet: &Alphabet,
+    pattern: Option<&str>,
+    include: Option<&str>,
+    exclude: Option<&str>,
+) -> Result<Vec<Clue>, ClueError> {
+    let letter = |ch: char| {
+        alphabet
+            .board_letter(ch)
+            .ok_or(ClueError::InvalidLetter(ch))
+    };
+    let mut res = vec![];
+    if let Some(pattern) = pattern {
+        for (i, ch) in pattern.chars().enumerate() {
+            if i > 4 {
+                return Err(ClueError::InvalidPattern(pattern.to_owned()));
+            }
+            if ch == '-' {
+                continue;
+            }
+            res.push(Clue::Green(letter(ch)?, i));
+        }
+    }
+    for ch in include.unwrap_or_default().chars() {
+        res.push(Clue::Yellow(letter(ch)?));
+    }
+    for ch in exclude.unwrap_or_default().chars() {
+        res.push(Clue::Gray(letter(ch)?));
+    }
+    Ok(res)
+}
+
+#[derive(Debug)]
+pub enum ClueError {
+    InvalidLetter(char),
+    InvalidPattern(String),
 }
 
+impl std::fmt::Display for ClueError {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ClueError::InvalidLetter(ch) => write!(fmt, "invalid letter {:?}", ch),
+            ClueError::InvalidPattern(pattern) => write!(fmt, "invalid pattern \"{}\"", pattern),
+        }
+    }
+}
+
+impl std::error::Error for ClueError {}
+
+#[allow(clippy::too_many_arguments)]
 fn search(
+    dict: &Dictionary,
     res: &mut HashSet<String>,
     work: &mut [Letter; 5],
     i: usize,
@@ -42,14 +112,14 @@ fn search(
 ) {
     if i == work.len() {
         if node.terminal && satisfies_rules(work, known, include) {
-            res.insert(work.iter().map(|l| letter_for_pos(*l)).collect());
+            res.insert(dict.spell(work));
         }
         return;
     }
     for l in letter_choices(&work[0..i], known[i], include, ok) {
         if let Some(n) = node.lookup(l) {
             work[i] = l;
-            search(res, work, i + 1, known, include, ok, n);
+            search(dict, res, work, i + 1, known, include, ok, n);
         }
     }
 }
@@ -99,3 +169,33 @@ fn letter_choices(
         .map(|(i, _)| Letter::for_i(i))
         .collect()
 }
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::{clues, run};
+    use crate::alphabet::Alphabet;
+    use crate::dictionary::build;
+
+    #[test]
+    fn other_alphabets() {
+        let spanish = Arc::new(Alphabet::named("spanish").unwrap());
+        let dict = build(["baños", "nacer", "ruido"], spanish.clone());
+        let found = run(
+            &clues(&spanish, Some("-a---"), Some("ñ"), None).unwrap(),
+            &dict,
+        );
+        assert_eq!(found, vec!["baños"]);
+        let found = run(&clues(&spanish, None, Some("u"), Some("ñ")).unwrap(), &dict);
+        assert_eq!(found, vec!["ruido"]);
+
+        let dutch = Arc::new(Alphabet::named("dutch").unwrap());
+        let dict = build(["ijzers", "ijsjes", "water"], dutch.clone());
+        let found = run(
+            &clues(&dutch, Some("ĳ----"), Some("z"), None).unwrap(),
+            &dict,
+        );
+        assert_eq!(found, vec!["ijzers"]);
+    }
+}
@@ -1,52 +1,111 @@
-use super::dictionary::{self, Q, U};
-use serde::Serialize;
+use super::alphabet::Alphabet;
+use super::dictionary::{self, Letter};
+use super::frequency::Filter;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use std::cmp::Reverse;
-use std::collections::HashSet;
-use std::error::Error;
-use std::fs::read_to_string;
+use std::collections::{HashMap, HashSet};
 use std::ops::Index;
 
-pub fn find_all_in_file(
-    path: &str,
-    dict: dictionary::Dictionary,
-    defs: dictionary::Definitions,
-    show_all: bool,
-) -> Result<(), Box<dyn Error>> {
-    let raw_board = read_to_string(path)?;
-    let board = boggled(&raw_board)?;
-
-    let t = std::time::Instant::now();
-    let words = find_words(&dict, &board);
-    dictionary::report_time("find_words", t);
-
-    let total_score: u32 = words.iter().map(|w| score(w)).sum();
-    println!("{}", raw_board);
-    println!(
-        "found {} words, {} points, {:.2} per word",
-        words.len(),
+#[derive(Serialize)]
+pub struct Solution {
+    /// The board, one string per row.
+    pub board: Vec<String>,
+    pub total_words: usize,
+    pub total_score: u32,
+    /// All of the words, best first.
+    pub words: Vec<SolvedWord>,
+}
+
+#[derive(Serialize)]
+pub struct SolvedWord {
+    pub word: String,
+    pub score: u32,
+    /// The positions of the letters of the word on the board.
+    pub path: Vec<Pos>,
+    pub def: Option<String>,
+}
+
+/// What `solve` includes in a solution. The default is every word, without definitions.
+#[derive(Default)]
+pub struct SolveOptions<'a> {
+    /// Where to look up definitions. A note from the dictionary's overlay wins.
+    pub defs: Option<&'a dictionary::Definitions>,
+    /// Only list words that are at least this common.
+    pub filter: Option<&'a Filter>,
+    /// List at most this many of the best words.
+    pub limit: Option<usize>,
+    /// How to score words. Without it, words are scored with `score` and no word is too short.
+    pub scoring: Option<&'a Scoring>,
+}
+
+/// Find all the words on the board, with their paths and definitions. The totals are for every
+/// word on the board, even when the options leave some of them out of the list.
+///
+/// ```
+/// use boggle_ocr::{boggled, build_dictionary, solve, Definitions, SolveOptions};
+///
+/// let dict = build_dictionary(vec!["cat", "cats", "tacs"]);
+/// let defs = Definitions::from([("cat".to_owned(), "mrow".to_owned())]);
+/// let board = boggled("cats\nxxxx\nxxxx\nxxxx\n")?;
+/// let opts = SolveOptions {
+///     defs: Some(&defs),
+///     limit: Some(1),
+///     ..Default::default()
+/// };
+/// let solution = solve(&dict, &board, &opts);
+/// assert_eq!(solution.total_words, 2);
+/// assert_eq!(solution.words[0].word, "cats");
+/// assert_eq!(solution.words.len(), 1);
+/// # Ok::<(), boggle_ocr::WSError>(())
+/// ```
+pub fn solve(dict: &dictionary::Dictionary, board: &Board, opts: &SolveOptions) -> Solution {
+    let mut words: Vec<SolvedWord> = find_word_paths(dict, board)
+        .into_iter()
+        .filter(|(word, _)| match opts.scoring {
+            Some(scoring) => scoring.counts(word),
+            None => true,
+        })
+        .map(|(word, path)| SolvedWord {
+            score: match opts.scoring {
+                Some(scoring) => scoring.score(&word),
+                None => score(&word),
+            },
+            def: match (dict.note(&word), opts.defs) {
+                (Some(note), _) => Some(note.to_owned()),
+                (None, Some(defs)) => defs.get(&word).map(str::to_owned),
+                (None, None) => None,
+            },
+            word,
+            path,
+        })
+        .collect();
+    words.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then(b.word.len().cmp(&a.word.len()))
+            .then(a.word.cmp(&b.word))
+    });
+    let total_words = words.len();
+    let total_score = words.iter().map(|w| w.score).sum();
+    if let Some(filter) = opts.filter {
+        words.retain(|w| filter.keep(dict, &w.word));
+    }
+    if let Some(limit) = opts.limit {
+        words.truncate(limit);
+    }
+    Solution {
+        board: board.rows_in(&dict.alphabet),
+        total_words,
         total_score,
-        total_score as f32 / words.len() as f32,
-    );
-    println!("best words:");
-    let max_count = match show_all {
-        true => None,
-        false => Some(20),
-    };
-    for (w, s) in best_words(&words, max_count) {
-        let def = match defs.get(&w) {
-            Some(def) => def.to_owned(),
-            None => "".to_owned(),
-        };
-        println!("  {s:2} {w:13} {def}");
+        words,
     }
-
-    Ok(())
 }
 
-pub fn best_words(words: &[String], count: Option<usize>) -> Vec<(String, u32)> {
+pub fn best_words(words: &[String], scoring: &Scoring, count: Option<usize>) -> Vec<(String, u32)> {
     let mut sortable_words: Vec<(Reverse<u32>, Reverse<usize>, &String)> = words
         .iter()
-        .map(|w| (Reverse(score(w)), Reverse(w.len()), w))
+        .map(|w| (Reverse(scoring.score(w)), Reverse(w.len()), w))
         .collect();
     sortable_words.sort();
 
@@ -64,37 +123,54 @@ pub fn find_boggle_words(
     board: &[&str],
     dict: &dictionary::Dictionary,
     min_length: usize,
-) -> Vec<Word> {
-    let board: AnyBoard = board.iter().map(|line| parse_board_line(line)).collect();
+) -> Result<Vec<Word>, WSError> {
+    let board = board
+        .iter()
+        .enumerate()
+        .map(|(row, line)| parse_board_line(row, line, &dict.alphabet))
+        .collect::<Result<AnyBoard, WSError>>()?;
+    let qu = dict.alphabet.qu();
     let total_letters = board.iter().map(|line| line.len()).sum();
     let mut res = HashSet::new();
     let mut scratch = Vec::with_capacity(total_letters);
     let pos_keeper = PositionKeeper::new(&board);
-    for i in 0..board.len() {
-        for j in 0..board[i].len() {
-            let pos = (i, j);
-            visit2(
-                pos,
-                pos_keeper.mark(0, pos),
-                &pos_keeper,
-                &board,
-                &dict.root,
-                &mut res,
-                &mut scratch,
-            );
+    for root in dict.roots() {
+        for i in 0..board.len() {
+            for j in 0..board[i].len() {
+                let pos = (i, j);
+                visit2(
+                    pos,
+                    pos_keeper.mark(0, pos),
+                    &pos_keeper,
+                    &board,
+                    qu,
+                    root,
+                    &mut res,
+                    &mut scratch,
+                );
+            }
         }
     }
     let mut res: Vec<Word> = res
         .into_iter()
-        .map(Word::new)
-        .filter(|w| w.word.len() >= min_length)
+        .map(|w| Word::new(dict.alphabet.board_word(&w)))
+        .filter(|w| w.word.chars().count() >= min_length && dict.allowed(&w.word))
         .collect();
     res.sort();
-    res
+    Ok(res)
 }
 
-fn parse_board_line(line: &str) -> Vec<dictionary::Letter> {
-    line.chars().map(dictionary::letter_pos).collect()
+fn parse_board_line(row: usize, line: &str, alphabet: &Alphabet) -> Result<Vec<Letter>, WSError> {
+    line.chars()
+        .enumerate()
+        .map(|(col, ch)| {
+            alphabet.board_letter(ch).ok_or(WSError::BadTile {
+                row: row + 1,
+                col: col + 1,
+                ch,
+            })
+        })
+        .collect()
 }
 
 #[derive(Serialize)]
@@ -104,10 +180,9 @@ pub struct Word {
 }
 
 impl Word {
-    fn new(word: Vec<dictionary::Letter>) -> Self {
-        let w = stringify_word(word);
-        let s = score(&w);
-        Self { word: w, score: s }
+    fn new(word: String) -> Self {
+        let score = score(&word);
+        Self { word, score }
     }
 }
 
@@ -131,9 +206,17 @@ impl PartialEq<Word> for Word {
 
 impl Eq for Word {}
 
-/// Get the boggle score for a word.
+/// Get the boggle score for a word: 1 point for 3 or 4 letters, then 2, 3, 5, and 11 for 8 or more.
+///
+/// ```
+/// use boggle_ocr::wordsearch::score;
+///
+/// assert_eq!(score("cat"), 1);
+/// assert_eq!(score("quiet"), 2);
+/// assert_eq!(score("boggling"), 11);
+/// ```
 pub fn score(word: &str) -> u32 {
-    match word.len() {
+    match word.chars().count() {
         0..=2 => 0,
         3 | 4 => 1,
         5 => 2,
@@ -143,63 +226,169 @@ pub fn score(word: &str) -> u32 {
     }
 }
 
-pub fn find_words(dict: &dictionary::Dictionary, board: &Board) -> Vec<String> {
-    let mut res = HashSet::new();
-    let mut scratch = Vec::with_capacity(25);
-    for i in 0..board.size() {
-        for j in 0..board.size() {
-            let pos = (i, j);
-            visit(
-                pos,
-                mark_visit(0, pos),
-                board,
-                &dict.root,
-                &mut res,
-                &mut scratch,
-            );
+/// House scoring rules: words shorter than `min_length` don't count, and `points` has the points
+/// for words of `min_length` letters, then one letter longer, and so on. The last entry is for all
+/// longer words. The default is the same as `score`.
+///
+/// ```
+/// use boggle_ocr::Scoring;
+///
+/// let house = Scoring {
+///     min_length: 4,
+///     points: vec![1, 2, 4],
+/// };
+/// assert!(!house.counts("cat"));
+/// assert_eq!(house.score("cats"), 1);
+/// assert_eq!(house.score("boggling"), 4);
+/// assert_eq!(Scoring::default().score("cat"), 1);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Scoring {
+    pub min_length: usize,
+    pub points: Vec<u32>,
+}
+
+impl Default for Scoring {
+    fn default() -> Self {
+        Self {
+            min_length: 3,
+            points: vec![1, 1, 2, 3, 5, 11],
         }
     }
-    let mut res: Vec<String> = res.into_iter().map(stringify_word).collect();
-    res.sort();
-    res
 }
 
-fn stringify_word(nw: Vec<dictionary::Letter>) -> String {
-    nw.into_iter()
-        .flat_map(|ch| QU::new(dictionary::letter_for_pos(ch)))
-        .collect()
+impl Scoring {
+    /// Whether a word is long enough to count.
+    pub fn counts(&self, word: &str) -> bool {
+        word.chars().count() >= self.min_length
+    }
+
+    pub fn score(&self, word: &str) -> u32 {
+        let len = word.chars().count();
+        if len < self.min_length {
+            return 0;
+        }
+        let i = len - self.min_length;
+        match self.points.get(i) {
+            Some(points) => *points,
+            None => self.points.last().copied().unwrap_or(0),
+        }
+    }
 }
 
-struct QU {
-    letter: char,
-    pos: usize,
+/// Find all the words on the board, in alphabetical order.
+pub fn find_words(dict: &dictionary::Dictionary, board: &Board) -> Vec<String> {
+    find_word_paths(dict, board)
+        .into_iter()
+        .map(|(w, _)| w)
+        .collect()
 }
 
-impl QU {
-    fn new(letter: char) -> Self {
-        Self { letter, pos: 0 }
+/// Find all the words on the board, along with the first path found for each one.
+pub fn find_word_paths(dict: &dictionary::Dictionary, board: &Board) -> Vec<(String, Vec<Pos>)> {
+    let mut res = HashMap::new();
+    let mut scratch = Vec::with_capacity(board.size() * board.size());
+    let qu = dict.alphabet.qu();
+    for root in dict.roots() {
+        for i in 0..board.size() {
+            for j in 0..board.size() {
+                let pos = (i, j);
+                visit(
+                    pos,
+                    mark_visit(board, 0, pos),
+                    board,
+                    qu,
+                    root,
+                    &mut res,
+                    &mut scratch,
+                );
+            }
+        }
     }
+    let mut res: Vec<(String, Vec<Pos>)> = res
+        .into_iter()
+        .map(|(w, path)| (dict.alphabet.board_word(&w), path))
+        .filter(|(w, _)| dict.allowed(w))
+        .collect();
+    res.sort();
+    res
 }
 
-impl Iterator for QU {
-    type Item = char;
+/// How many tiles a word takes on a board, where "qu" is one Q tile. Returns None if the word has
+/// characters that aren't in the alphabet.
+pub fn tile_count(alphabet: &Alphabet, word: &str) -> Option<usize> {
+    let letters = alphabet.letters(word)?;
+    let qu = match alphabet.qu() {
+        Some((q, u)) => letters.windows(2).filter(|w| *w == [q, u]).count(),
+        None => 0,
+    };
+    Some(letters.len() - qu)
+}
 
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.pos {
-            0 => {
-                self.pos += 1;
-                Some(self.letter)
+/// Find a path that spells a word on the board. "qu" in the word matches a Q on the board.
+pub fn find_path(board: &Board, alphabet: &Alphabet, word: &str) -> Option<Vec<Pos>> {
+    let mut letters = vec![];
+    let mut word = alphabet.letters(word)?.into_iter();
+    while let Some(l) = word.next() {
+        if let Some((q, u)) = alphabet.qu() {
+            if l == q && word.next() != Some(u) {
+                return None;
             }
-            1 => match self.letter {
-                'q' => {
-                    self.pos += 1;
-                    Some('u')
-                }
-                _ => None,
-            },
-            _ => None,
         }
+        letters.push(l);
     }
+    let mut scratch = vec![];
+    for i in 0..board.size() {
+        for j in 0..board.size() {
+            if trace_path(board, &letters, (i, j), 0, &mut scratch) {
+                return Some(scratch);
+            }
+        }
+    }
+    None
+}
+
+fn trace_path(
+    board: &Board,
+    letters: &[dictionary::Letter],
+    pos: Pos,
+    visited: Visited,
+    scratch: &mut Vec<Pos>,
+) -> bool {
+    let (i, j) = pos;
+    if letters.is_empty() {
+        return true;
+    }
+    let nvisited = mark_visit(board, visited, pos);
+    if nvisited == visited || board[i][j] != letters[0] {
+        return false;
+    }
+    scratch.push(pos);
+    if letters.len() == 1 {
+        return true;
+    }
+    let sz = board.size() as isize;
+    for di in -1..=1 {
+        for dj in -1..=1 {
+            let ni = di + i as isize;
+            let nj = dj + j as isize;
+            let in_bounds = ni >= 0 && nj >= 0 && ni < sz && nj < sz;
+            if in_bounds
+                && trace_path(
+                    board,
+                    &letters[1..],
+                    (ni as usize, nj as usize),
+                    nvisited,
+                    scratch,
+                )
+            {
+                return true;
+            }
+        }
+    }
+    scratch.pop();
+    false
 }
 
 struct PositionKeeper {
@@ -224,18 +413,20 @@ impl PositionKeeper {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn visit2(
     pos: Pos,
     visited: Visited,
     pk: &PositionKeeper,
     board: &AnyBoard,
+    qu: Option<(Letter, Letter)>,
     node: &dictionary::Node,
     res: &mut HashSet<Vec<dictionary::Letter>>,
     scratch: &mut Vec<dictionary::Letter>,
 ) {
     let (i, j) = pos;
     let ch = board[i][j];
-    if let Some(next_node) = lookup(node, ch) {
+    if let Some(next_node) = lookup(node, ch, qu) {
         scratch.push(ch);
         if next_node.terminal {
             res.insert(scratch.clone());
@@ -252,7 +443,7 @@ fn visit2(
                 let npos = (ni as usize, nj as usize);
                 let nvisited = pk.mark(visited, npos);
                 if nvisited != visited {
-                    visit2(npos, nvisited, pk, board, next_node, res, scratch);
+                    visit2(npos, nvisited, pk, board, qu, next_node, res, scratch);
                 }
             }
         }
@@ -281,17 +472,19 @@ fn visit(
     pos: Pos,
     visited: Visited,
     board: &Board,
+    qu: Option<(Letter, Letter)>,
     node: &dictionary::Node,
-    res: &mut HashSet<Vec<dictionary::Letter>>,
-    scratch: &mut Vec<dictionary::Letter>,
+    res: &mut HashMap<Vec<dictionary::Letter>, Vec<Pos>>,
+    scratch: &mut Vec<Pos>,
 ) {
     let (i, j) = pos;
     let ch = board[i][j];
     let sz = board.size() as isize;
-    if let Some(next_node) = lookup(node, ch) {
-        scratch.push(ch);
+    if let Some(next_node) = lookup(node, ch, qu) {
+        scratch.push(pos);
         if next_node.terminal && scratch.len() >= board.min_word_size() {
-            res.insert(scratch.clone());
+            let word = scratch.iter().map(|(i, j)| board[*i][*j]).collect();
+            res.entry(word).or_insert_with(|| scratch.clone());
         }
         for di in -1..=1 {
             for dj in -1..=1 {
@@ -300,9 +493,9 @@ fn visit(
                     let nj = dj + j as isize;
                     if ni >= 0 && nj >= 0 && ni < sz && nj < sz {
                         let npos = (ni as usize, nj as usize);
-                        let nvisited = mark_visit(visited, npos);
+                        let nvisited = mark_visit(board, visited, npos);
                         if nvisited != visited {
-                            visit(npos, nvisited, board, next_node, res, scratch);
+                            visit(npos, nvisited, board, qu, next_node, res, scratch);
                         }
                     }
                 }
@@ -312,20 +505,26 @@ fn visit(
     }
 }
 
-fn lookup(node: &dictionary::Node, ch: dictionary::Letter) -> Option<&dictionary::Node> {
-    match node.lookup(ch) {
-        Some(child) if ch == Q => child.lookup(U),
-        res => res,
+// Follow a board letter. Q on the board is "qu".
+fn lookup(
+    node: &dictionary::Node,
+    ch: Letter,
+    qu: Option<(Letter, Letter)>,
+) -> Option<&dictionary::Node> {
+    match (node.lookup(ch), qu) {
+        (Some(child), Some((q, u))) if ch == q => child.lookup(u),
+        (res, _) => res,
     }
 }
 
-fn mark_visit(visited: Visited, pos: Pos) -> Visited {
-    let bit = pos.0 * 5 + pos.1;
+fn mark_visit(board: &Board, visited: Visited, pos: Pos) -> Visited {
+    let bit = pos.0 * board.size() + pos.1;
     visited | (1 << bit)
 }
 
-type Visited = u32;
-type Pos = (usize, usize);
+type Visited = u64;
+/// A (row, column) position on the board.
+pub type Pos = (usize, usize);
 
 type AnyBoard = Vec<Vec<dictionary::Letter>>;
 
@@ -333,20 +532,29 @@ type AnyBoard = Vec<Vec<dictionary::Letter>>;
 pub enum Board {
     Small([[dictionary::Letter; 4]; 4]),
     Large([[dictionary::Letter; 5]; 5]),
+    Huge([[dictionary::Letter; 6]; 6]),
 }
 
 impl Board {
-    fn size(&self) -> usize {
+    pub fn size(&self) -> usize {
         match self {
             Board::Small(_) => 4,
             Board::Large(_) => 5,
+            Board::Huge(_) => 6,
         }
     }
 
-    fn min_word_size(&self) -> usize {
+    /// The letters on the board, one string per row, in upper case.
+    pub fn rows_in(&self, alphabet: &Alphabet) -> Vec<String> {
+        (0..self.size())
+            .map(|i| self[i].iter().map(|l| alphabet.board_text(*l)).collect())
+            .collect()
+    }
+
+    pub fn min_word_size(&self) -> usize {
         match self {
             Board::Small(_) => 3,
-            Board::Large(_) => 4,
+            Board::Large(_) | Board::Huge(_) => 4,
         }
     }
 }
@@ -358,20 +566,53 @@ impl Index<usize> for Board {
         match self {
             Board::Small(b) => &b[index],
             Board::Large(b) => &b[index],
+            Board::Huge(b) => &b[index],
         }
     }
 }
 
+/// Parse an English board. Whitespace between tiles is ignored.
+///
+/// ```
+/// use boggle_ocr::{boggled, WSError};
+///
+/// let board = boggled("abcd efgh ijkl mnop")?;
+/// assert_eq!(board.size(), 4);
+/// assert_eq!(boggled("abc"), Err(WSError::WrongTileCount(3)));
+/// # Ok::<(), WSError>(())
+/// ```
 pub fn boggled(raw: &str) -> Result<Board, WSError> {
+    boggled_in(raw, &Alphabet::english())
+}
+
+/// Parse a board, one character per tile. A Q is "Qu".
+///
+/// ```
+/// use boggle_ocr::{boggled_in, Alphabet};
+///
+/// let spanish = Alphabet::named("spanish").unwrap();
+/// let board = boggled_in("ñaba\nxxxx\nxxxx\nxxxx\n", &spanish)?;
+/// assert_eq!(board.rows_in(&spanish)[0], "ÑABA");
+/// # Ok::<(), boggle_ocr::WSError>(())
+/// ```
+pub fn boggled_in(raw: &str, alphabet: &Alphabet) -> Result<Board, WSError> {
     let mut l = Vec::new();
-    for ch in raw.chars() {
-        match ch {
-            'a'..='z' | 'A'..='Z' => {
-                l.push(dictionary::letter_pos(ch));
-            }
-            ' ' | '\n' | '\t' => {}
-            _ => return Err(WSError::InvalidBoard(format!("invalid char {:?}", ch))),
-        };
+    for (row, line) in raw.lines().enumerate() {
+        for (col, ch) in line.chars().enumerate() {
+            match ch {
+                ' ' | '\t' | '\r' => {}
+                ch => match alphabet.board_letter(ch) {
+                    Some(letter) => l.push(letter),
+                    None => {
+                        return Err(WSError::BadTile {
+                            row: row + 1,
+                            col: col + 1,
+                            ch,
+                        })
+                    }
+                },
+            };
+        }
     }
     match l.len() {
         16 => Ok(Board::Small([
@@ -387,27 +628,44 @@ pub fn boggled(raw: &str) -> Result<Board, WSError> {
             [l[15], l[16], l[17], l[18], l[19]],
             [l[20], l[21], l[22], l[23], l[24]],
         ])),
-        n => Err(WSError::InvalidBoard(format!(
-            "board must have 16 or 25 letters, not {n}"
-        ))),
+        36 => Ok(Board::Huge([
+            [l[0], l[1], l[2], l[3], l[4], l[5]],
+            [l[6], l[7], l[8], l[9], l[10], l[11]],
+            [l[12], l[13], l[14], l[15], l[16], l[17]],
+            [l[18], l[19], l[20], l[21], l[22], l[23]],
+            [l[24], l[25], l[26], l[27], l[28], l[29]],
+            [l[30], l[31], l[32], l[33], l[34], l[35]],
+        ])),
+        n => Err(WSError::WrongTileCount(n)),
     }
 }
 
-// TODO - use thiserror
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum WSError {
-    InvalidBoard(String),
+    /// A character that isn't a tile. Rows and columns count from 1.
+    BadTile { row: usize, col: usize, ch: char },
+    /// Boards have 16, 25, or 36 tiles.
+    WrongTileCount(usize),
 }
 
 impl std::fmt::Display for WSError {
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
-            WSError::InvalidBoard(reason) => write!(fmt, "invalid board: {}", reason),
+            WSError::BadTile { row, col, ch } => write!(
+                fmt,
+                "invalid board: {:?} at row {}, column {} isn't a tile",
+                ch, row, col
+            ),
+            WSError::WrongTileCount(n) => write!(
+                fmt,
+                "invalid board: board must have 16, 25, or 36 letters, not {}",
+                n
+            ),
         }
     }
 }
 
-impl Error for WSError {}
+impl std::error::Error for WSError {}
 
 #[cfg(test)]
 mod test {
@@ -415,14 +673,15 @@ mod test {
 
     use crate::wordsearch::Board;
 
-    use super::boggled;
-    use super::dictionary::{build_dictionary, l};
+    use super::dictionary::{self, build_dictionary, l};
+    use super::{boggled, boggled_in, Alphabet, WSError};
 
     #[test]
     fn example() {
         let dict = build_dictionary(vec!["tenets", "facts", "honey"]);
         let res =
-            super::find_boggle_words(&vec!["taeyl", "eohak", "yneit", "yteyl", "shaig"], &dict, 3);
+            super::find_boggle_words(&vec!["taeyl", "eohak", "yneit", "yteyl", "shaig"], &dict, 3)
+                .unwrap();
         let words: Vec<String> = res.into_iter().map(|w| w.word).collect();
         assert_eq!(words, vec!["honey", "tenets"]);
     }
@@ -430,11 +689,106 @@ mod test {
     #[test]
     fn example_with_implied_u_after_q() {
         let dict = build_dictionary(vec!["quit", "quick"]);
-        let res = super::find_boggle_words(&vec!["qic", "xkk"], &dict, 3);
+        let res = super::find_boggle_words(&vec!["qic", "xkk"], &dict, 3).unwrap();
         let words: Vec<String> = res.into_iter().map(|w| w.word).collect();
         assert_eq!(words, vec!["quick"]);
     }
 
+    #[test]
+    fn other_alphabets() {
+        let german = std::sync::Arc::new(Alphabet::named("german").unwrap());
+        let dict = dictionary::build(["süß", "süße", "fuß", "sus"], german.clone());
+        let board = boggled_in("süßE\nfxxx\nxxxx\nxxxx\n", &german).unwrap();
+        assert_eq!(super::find_words(&dict, &board), vec!["süß", "süße"]);
+        assert_eq!(board.rows_in(&german)[0], "SÜßE");
+        assert!(boggled("süße\nfxxx\nxxxx\nxxxx\n").is_err());
+
+        let dutch = std::sync::Arc::new(Alphabet::named("dutch").unwrap());
+        let dict = dictionary::build(["ijs", "wijs"], dutch.clone());
+        let board = boggled_in("wĳsx\nxxxx\nxxxx\nxxxx\n", &dutch).unwrap();
+        assert_eq!(super::find_words(&dict, &board), vec!["wijs"]);
+        assert_eq!(
+            super::find_path(&board, &dutch, "IJS"),
+            Some(vec![(0, 1), (0, 2)])
+        );
+    }
+
+    #[test]
+    fn solve_with_paths() {
+        let dict = build_dictionary(vec!["cat", "cats", "act"]);
+        let board = boggled("cats\nxxxx\nxxxx\nxxxx\n").unwrap();
+        let defs = super::dictionary::Definitions::from([("cat".to_owned(), "mrow".to_owned())]);
+        let opts = super::SolveOptions {
+            defs: Some(&defs),
+            ..Default::default()
+        };
+        let solution = super::solve(&dict, &board, &opts);
+        assert_eq!(solution.board, vec!["CATS", "XXXX", "XXXX", "XXXX"]);
+        assert_eq!(solution.total_score, 2);
+        let cats = &solution.words[0];
+        assert_eq!(cats.word, "cats");
+        assert_eq!(cats.path, vec![(0, 0), (0, 1), (0, 2), (0, 3)]);
+        assert_eq!(cats.def, None);
+        let cat = &solution.words[1];
+        assert_eq!(cat.word, "cat");
+        assert_eq!(cat.path, vec![(0, 0), (0, 1), (0, 2)]);
+        assert_eq!(cat.def.as_deref(), Some("mrow"));
+    }
+
+    #[test]
+    fn house_scoring() {
+        let standard = super::Scoring::default();
+        for word in [
+            "at",
+            "cat",
+            "cats",
+            "facts",
+            "facets",
+            "acrobat",
+            "acrobats",
+            "acrobatics",
+        ] {
+            assert_eq!(standard.score(word), super::score(word), "{}", word);
+        }
+
+        let dict = build_dictionary(vec!["cat", "cats", "act"]);
+        let board = boggled("cats\nxxxx\nxxxx\nxxxx\n").unwrap();
+        let scoring = super::Scoring {
+            min_length: 4,
+            points: vec![3],
+        };
+        let opts = super::SolveOptions {
+            scoring: Some(&scoring),
+            ..Default::default()
+        };
+        let solution = super::solve(&dict, &board, &opts);
+        assert_eq!(solution.total_words, 1);
+        assert_eq!(solution.total_score, 3);
+        assert_eq!(
+            super::solve(&dict, &board, &Default::default()).total_words,
+            2
+        );
+    }
+
+    #[test]
+    fn find_paths() {
+        let board = boggled("qitx\nxxxx\nxxxs\nxxxe\n").unwrap();
+        assert_eq!(
+            super::find_path(&board, &Alphabet::english(), "quit"),
+            Some(vec![(0, 0), (0, 1), (0, 2)])
+        );
+        assert_eq!(
+            super::find_path(&board, &Alphabet::english(), "QUIT"),
+            Some(vec![(0, 0), (0, 1), (0, 2)])
+        );
+        assert_eq!(super::find_path(&board, &Alphabet::english(), "qit"), None);
+        assert_eq!(super::find_path(&board, &Alphabet::english(), "tit"), None);
+        assert_eq!(
+            super::find_path(&board, &Alphabet::english(), "xse"),
+            Some(vec![(1, 2), (2, 3), (3, 3)])
+        );
+    }
+
     #[test]
     fn boggled_too_short() {
         let res = boggled("abcde fghij klmno pqrst uvwx");
@@ -453,6 +807,22 @@ mod test {
         assert!(boggled("1bcde fghij klmno pqrst uvwxy").is_err());
     }
 
+    #[test]
+    fn boggled_errors() {
+        assert_eq!(
+            boggled("abcd\nef1h\nijkl\nmnop\n").err(),
+            Some(WSError::BadTile {
+                row: 2,
+                col: 3,
+                ch: '1'
+            })
+        );
+        assert_eq!(
+            boggled("abcd\nefgh\n").err(),
+            Some(WSError::WrongTileCount(8))
+        );
+    }
+
     #[test]
     fn boggled_lines() {
         assert_eq!(
@@ -480,6 +850,23 @@ mod test {
         );
     }
 
+    #[test]
+    fn boggled_huge() {
+        let board = boggled("abcdef\nghijkl\nmnopqr\nstuvwx\nyzabcd\nefghij\n").unwrap();
+        assert_eq!(board.size(), 6);
+        assert_eq!(board[5][5], l('j'));
+    }
+
+    #[test]
+    fn huge_board_uses_all_cells() {
+        let dict = build_dictionary(vec!["abcdefghijkl", "xwvu"]);
+        let board = boggled("abcdef\nlkjihg\nmnopqr\nstuvwx\nyzabcd\nefghij\n").unwrap();
+        assert_eq!(
+            super::find_words(&dict, &board),
+            vec!["abcdefghijkl", "xwvu"]
+        );
+    }
+
     #[test]
     fn boggled_caps() {
         assert_eq!(